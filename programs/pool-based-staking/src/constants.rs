@@ -1,2 +1,20 @@
-pub const TOTAL_COLLECTION_COUNT: usize = 3986;
-pub const MAX_NFT_PER_USER: usize = 300;
\ No newline at end of file
+pub const MAX_NFT_PER_USER: u32 = 200;
+// Fixed point scale applied to `Vault::reward_per_share`.
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000;
+// Reward weight of an NFT when the vault has no weights root.
+pub const DEFAULT_NFT_WEIGHT: u64 = 1;
+pub const MAX_LOCK_TIERS: usize = 4;
+pub const BPS_DENOMINATOR: u64 = 10_000;
+pub const MAX_BOOSTERS: usize = 8;
+// `Vault::emission_mode` values.
+pub const EMISSION_MODE_ROUNDS: u8 = 0;
+pub const EMISSION_MODE_CONTINUOUS: u8 = 1;
+// Upper bound on `stake_fee` and `unstake_fee`, in lamports or `fee_mint` base units.
+pub const MAX_FEE: u64 = 10_000_000_000;
+pub const MAX_CLAIM_FEE_BPS: u64 = 2_000;
+pub const MAX_MULTISIG_SIGNERS: usize = 5;
+pub const MAX_TIMELOCK_DELAY: u64 = 30 * 24 * 60 * 60;
+// `Vault::paused` flags.
+pub const PAUSE_STAKE: u8 = 1;
+pub const PAUSE_CLAIM: u8 = 1 << 1;
+pub const PAUSE_FUND: u8 = 1 << 2;
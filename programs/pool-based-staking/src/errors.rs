@@ -11,9 +11,9 @@ pub enum CustomError {
   Unauthorized,
   #[msg("User does not beling to the vault")]
   IncorrectVault,
-  #[msg("NFT is not in collection")]
-  IncorrectTreasury,
   #[msg("Treasury Supplied doesn't belong to the vault")]
+  IncorrectTreasury,
+  #[msg("NFT is not in collection")]
   WrongNFT,
   #[msg("Mint should be equal")]
   MintMismatch,
@@ -21,4 +21,74 @@ pub enum CustomError {
   AccountMismatch,
   #[msg("Max number staked")]
   MaxStaked,
+  #[msg("Vault does not pay rewards in this currency")]
+  InvalidRewardMode,
+  #[msg("Merkle proof does not match the vault root")]
+  InvalidProof,
+  #[msg("Mint is not on the vault allowlist")]
+  NotAllowlisted,
+  #[msg("Lock tier is not configured")]
+  InvalidLockTier,
+  #[msg("NFT is still locked")]
+  StillLocked,
+  #[msg("Booster slot is out of range")]
+  InvalidBooster,
+  #[msg("NFT is not a registered booster")]
+  NotBooster,
+  #[msg("A booster is already staked")]
+  BoosterAlreadyStaked,
+  #[msg("Booster is not staked")]
+  BoosterNotStaked,
+  #[msg("Remaining accounts do not match the batch items")]
+  BatchAccountsMismatch,
+  #[msg("Unknown emission mode")]
+  InvalidEmissionMode,
+  #[msg("Payout schedule already started")]
+  ScheduleAlreadyStarted,
+  #[msg("Amount exceeds the rewards left in the budget")]
+  InsufficientRewardBudget,
+  #[msg("Vault balance cannot cover rent, fees and rewards owed")]
+  InsufficientVaultBalance,
+  #[msg("Discount cannot exceed 100%")]
+  InvalidDiscount,
+  #[msg("Fee exceeds the allowed maximum")]
+  InvalidFee,
+  #[msg("No authority transfer is pending")]
+  NoPendingAuthority,
+  #[msg("Payout interval must be greater than zero")]
+  InvalidInterval,
+  #[msg("Action requires multisig approval")]
+  MultisigRequired,
+  #[msg("Invalid multisig signers or threshold")]
+  InvalidMultisig,
+  #[msg("Signer is not a multisig signer")]
+  NotMultisigSigner,
+  #[msg("Proposal already approved by this signer")]
+  AlreadyApproved,
+  #[msg("Proposal already executed")]
+  ProposalExecuted,
+  #[msg("Proposal has not reached the approval threshold")]
+  ThresholdNotMet,
+  #[msg("Proposal index must match the vault proposal count")]
+  InvalidProposalIndex,
+  #[msg("Timelock delay exceeds the allowed maximum")]
+  InvalidTimelockDelay,
+  #[msg("No queued change is ready to execute")]
+  NoPendingChange,
+  #[msg("Staking is paused")]
+  StakingPaused,
+  #[msg("Claiming is paused")]
+  ClaimingPaused,
+  #[msg("Funding is paused")]
+  FundingPaused,
+  #[msg("Arithmetic overflow")]
+  MathOverflow,
+  #[msg("NFT is not staked")]
+  NotStaked,
+  #[msg("Clock sysvar is unavailable")]
+  ClockUnavailable,
+  #[msg("Vault cannot hold more staked weight")]
+  VaultFull,
+  #[msg("Queued drain is still timelocked")]
+  DrainTimelocked,
 }
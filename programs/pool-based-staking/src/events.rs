@@ -0,0 +1,245 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::state::*;
+
+#[event]
+pub struct VaultInitialized {
+  pub vault: Pubkey,
+  pub authority: Pubkey,
+  pub pool_id: Pubkey,
+  pub creator_address: Pubkey,
+  pub collection_mint: Pubkey,
+  pub payout_interval: u64,
+  pub payout_amount: u64,
+  pub stake_fee: u64,
+  pub unstake_fee: u64,
+  pub timestamp: i64,
+}
+
+#[event]
+pub struct VaultUpdated {
+  pub vault: Pubkey,
+  pub authority: Pubkey,
+  pub change: VaultChange,
+  pub timestamp: i64,
+}
+
+#[event]
+pub struct Funded {
+  pub vault: Pubkey,
+  pub funder: Pubkey,
+  // Default key for SOL.
+  pub mint: Pubkey,
+  pub amount: u64,
+  pub total_amount: u64,
+  pub timestamp: i64,
+}
+
+// A drain waiting out the timelock, repeating the same drain once `eta` has passed sends the funds.
+#[event]
+pub struct DrainQueued {
+  pub vault: Pubkey,
+  pub authority: Pubkey,
+  pub amount: u64,
+  pub eta: u64,
+  pub timestamp: i64,
+}
+
+#[event]
+pub struct Drained {
+  pub vault: Pubkey,
+  pub authority: Pubkey,
+  // Default key for SOL.
+  pub mint: Pubkey,
+  pub amount: u64,
+  pub total_amount: u64,
+  pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityProposed {
+  pub vault: Pubkey,
+  pub authority: Pubkey,
+  pub pending_authority: Pubkey,
+  pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityAccepted {
+  pub vault: Pubkey,
+  pub previous_authority: Pubkey,
+  pub authority: Pubkey,
+  pub timestamp: i64,
+}
+
+#[event]
+pub struct PendingChangesExecuted {
+  pub vault: Pubkey,
+  pub executor: Pubkey,
+  pub stake_fee: u64,
+  pub unstake_fee: u64,
+  pub payout_interval: u64,
+  pub payout_amount: u64,
+  pub timelock_delay: u64,
+  pub fee_mint: Pubkey,
+  pub pass_mint: Pubkey,
+  pub pass_discount_bps: u64,
+  pub claim_fee_bps: u64,
+  pub batch_fee_once: bool,
+  pub emission_end_time: u64,
+  pub timestamp: i64,
+}
+
+#[event]
+pub struct PauseUpdated {
+  pub vault: Pubkey,
+  pub operator: Pubkey,
+  pub paused: u8,
+  pub emergency_mode: bool,
+  pub timestamp: i64,
+}
+
+#[event]
+pub struct RolesUpdated {
+  pub vault: Pubkey,
+  pub authority: Pubkey,
+  pub operator: Pubkey,
+  pub treasurer: Pubkey,
+  pub timestamp: i64,
+}
+
+#[event]
+pub struct FeesWithdrawn {
+  pub vault: Pubkey,
+  pub recipient: Pubkey,
+  pub mint: Pubkey,
+  pub amount: u64,
+  pub timestamp: i64,
+}
+
+#[event]
+pub struct PayoutScheduleStarted {
+  pub vault: Pubkey,
+  pub timestamp: i64,
+}
+
+#[event]
+pub struct PayoutRoundAdvanced {
+  pub vault: Pubkey,
+  pub payout_round: u32,
+  pub added_rounds: u32,
+  pub reward_per_share: u128,
+  pub timestamp: i64,
+}
+
+#[event]
+pub struct StakeAccountCreated {
+  pub vault: Pubkey,
+  pub user: Pubkey,
+  pub timestamp: i64,
+}
+
+#[event]
+pub struct Staked {
+  pub vault: Pubkey,
+  pub user: Pubkey,
+  pub mint: Pubkey,
+  pub weight: u64,
+  pub unlock_time: u64,
+  pub timestamp: i64,
+}
+
+#[event]
+pub struct Unstaked {
+  pub vault: Pubkey,
+  pub user: Pubkey,
+  pub mint: Pubkey,
+  pub weight: u64,
+  pub timestamp: i64,
+}
+
+#[event]
+pub struct EmergencyUnstaked {
+  pub vault: Pubkey,
+  pub user: Pubkey,
+  pub mint: Pubkey,
+  pub weight: u64,
+  pub forfeited_rewards: u64,
+  pub timestamp: i64,
+}
+
+#[event]
+pub struct BoosterEmergencyUnstaked {
+  pub vault: Pubkey,
+  pub user: Pubkey,
+  pub mint: Pubkey,
+  pub forfeited_rewards: u64,
+  pub timestamp: i64,
+}
+
+#[event]
+pub struct BoosterStaked {
+  pub vault: Pubkey,
+  pub user: Pubkey,
+  pub mint: Pubkey,
+  pub multiplier_bps: u64,
+  pub timestamp: i64,
+}
+
+#[event]
+pub struct BoosterUnstaked {
+  pub vault: Pubkey,
+  pub user: Pubkey,
+  pub mint: Pubkey,
+  pub timestamp: i64,
+}
+
+#[event]
+pub struct Claimed {
+  pub vault: Pubkey,
+  pub user: Pubkey,
+  // Default key for SOL.
+  pub mint: Pubkey,
+  // Paid to the staker, after the claim fee.
+  pub amount: u64,
+  // Sent to the fee treasury.
+  pub fee: u64,
+  pub timestamp: i64,
+}
+
+#[event]
+pub struct MultisigUpdated {
+  pub vault: Pubkey,
+  pub signers: [Pubkey; MAX_MULTISIG_SIGNERS],
+  pub threshold: u8,
+  pub timestamp: i64,
+}
+
+#[event]
+pub struct ProposalCreated {
+  pub vault: Pubkey,
+  pub proposal: Pubkey,
+  pub index: u64,
+  pub proposer: Pubkey,
+  pub action: ProposalAction,
+  pub timestamp: i64,
+}
+
+#[event]
+pub struct ProposalApproved {
+  pub vault: Pubkey,
+  pub proposal: Pubkey,
+  pub approver: Pubkey,
+  pub approvals: u8,
+  pub timestamp: i64,
+}
+
+#[event]
+pub struct ProposalExecuted {
+  pub vault: Pubkey,
+  pub proposal: Pubkey,
+  pub executor: Pubkey,
+  pub action: ProposalAction,
+  pub timestamp: i64,
+}
@@ -1,21 +1,38 @@
 use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token::{Mint, Token, TokenAccount};
 
 use crate::{state::*};
 
 #[derive(Accounts)]
+#[instruction(pool_id: Pubkey)]
 pub struct InitializeVault<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
 
     #[account(
-        mut,
+        init,
+        payer = authority,
         seeds =[
             b"vault".as_ref(),
+            pool_id.as_ref(),
         ],
-        bump
+        bump,
+        space = Vault::LEN + 8
     )]
     pub vault: AccountLoader<'info, Vault>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"fee_treasury".as_ref(),
+            vault.key().as_ref()
+        ],
+        bump
+    )]
+    pub fee_treasury: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
@@ -27,6 +44,49 @@ pub struct UpdateVault<'info> {
         mut,
         seeds = [
             b"vault".as_ref(),
+            vault.load()?.pool_id.as_ref(),
+        ],
+        bump = vault.load()?.bump
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    pub pending_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vault".as_ref(),
+            vault.load()?.pool_id.as_ref(),
+        ],
+        bump = vault.load()?.bump
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+}
+
+#[derive(Accounts)]
+pub struct ExecutePendingChanges<'info> {
+    pub executor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vault".as_ref(),
+            vault.load()?.pool_id.as_ref(),
+        ],
+        bump = vault.load()?.bump
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+}
+
+#[derive(Accounts)]
+pub struct ViewVault<'info> {
+    #[account(
+        seeds = [
+            b"vault".as_ref(),
+            vault.load()?.pool_id.as_ref(),
         ],
         bump = vault.load()?.bump
     )]
@@ -42,10 +102,13 @@ pub struct FundSolVault<'info> {
         mut,
         seeds = [
             b"vault".as_ref(),
+            vault.load()?.pool_id.as_ref(),
         ],
         bump = vault.load()?.bump
     )]
     pub vault: AccountLoader<'info, Vault>,
+
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
@@ -58,10 +121,239 @@ pub struct DrainSolVault<'info>
         mut,
         seeds = [
             b"vault".as_ref(),
+            vault.load()?.pool_id.as_ref(),
+        ],
+        bump = vault.load()?.bump
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vault".as_ref(),
+            vault.load()?.pool_id.as_ref(),
+        ],
+        bump = vault.load()?.bump
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"fee_treasury".as_ref(),
+            vault.key().as_ref()
+        ],
+        bump = vault.load()?.fee_treasury_bump
+    )]
+    pub fee_treasury: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeFeeTokenAccount<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [
+            b"vault".as_ref(),
+            vault.load()?.pool_id.as_ref(),
+        ],
+        bump = vault.load()?.bump
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    #[account(
+        seeds = [
+            b"fee_treasury".as_ref(),
+            vault.key().as_ref()
+        ],
+        bump = vault.load()?.fee_treasury_bump
+    )]
+    pub fee_treasury: SystemAccount<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    // The fee treasury's token account collecting fees paid in `mint`.
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = mint,
+        associated_token::authority = fee_treasury,
+    )]
+    pub fee_treasury_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFeeTokens<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vault".as_ref(),
+            vault.load()?.pool_id.as_ref(),
+        ],
+        bump = vault.load()?.bump
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    #[account(
+        seeds = [
+            b"fee_treasury".as_ref(),
+            vault.key().as_ref()
+        ],
+        bump = vault.load()?.fee_treasury_bump
+    )]
+    pub fee_treasury: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        token::authority = fee_treasury,
+    )]
+    pub fee_treasury_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = fee_treasury_token_account.mint,
+    )]
+    pub recipient_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeRewardTokenAccount<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vault".as_ref(),
+            vault.load()?.pool_id.as_ref(),
+        ],
+        bump = vault.load()?.bump
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    pub reward_mint: Account<'info, Mint>,
+
+    // The vault's token account holding the reward funds.
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = reward_mint,
+        associated_token::authority = vault,
+    )]
+    pub vault_reward_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [
+            b"fee_treasury".as_ref(),
+            vault.key().as_ref()
+        ],
+        bump = vault.load()?.fee_treasury_bump
+    )]
+    pub fee_treasury: SystemAccount<'info>,
+
+    // The fee treasury's token account collecting claim fees, already there when `reward_mint` is also the fee mint.
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = reward_mint,
+        associated_token::authority = fee_treasury,
+    )]
+    pub fee_treasury_reward_account: Box<Account<'info, TokenAccount>>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct FundTokenVault<'info> {
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vault".as_ref(),
+            vault.load()?.pool_id.as_ref(),
+        ],
+        bump = vault.load()?.bump
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    #[account(
+        mut,
+        token::mint = vault.load()?.reward_mint,
+        token::authority = funder,
+    )]
+    pub funder_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = vault.load()?.reward_mint,
+        associated_token::authority = vault,
+    )]
+    pub vault_reward_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct DrainTokenVault<'info> {
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vault".as_ref(),
+            vault.load()?.pool_id.as_ref(),
         ],
         bump = vault.load()?.bump
     )]
     pub vault: AccountLoader<'info, Vault>,
+
+    #[account(
+        mut,
+        token::mint = vault.load()?.reward_mint,
+    )]
+    pub funder_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = vault.load()?.reward_mint,
+        associated_token::authority = vault,
+    )]
+    pub vault_reward_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
 }
 
 #[derive(Accounts)]
@@ -69,12 +361,22 @@ pub struct CreateStakeAccount<'info> {
     #[account(mut)]
     pub creator: Signer<'info>,
 
+    #[account(
+        seeds = [
+            b"vault".as_ref(),
+            vault.load()?.pool_id.as_ref(),
+        ],
+        bump = vault.load()?.bump
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
     #[account(
         init,
         payer = creator,
         seeds = [
             b"user".as_ref(),
-            user.key().as_ref()
+            vault.key().as_ref(),
+            creator.key().as_ref()
         ],
         bump,
         space = User::LEN + 8
@@ -93,6 +395,7 @@ pub struct Stake<'info> {
         mut,
         seeds = [
             b"user".as_ref(),
+            vault.key().as_ref(),
             staker.key().as_ref()
         ],
         bump = user.bump
@@ -103,15 +406,51 @@ pub struct Stake<'info> {
         mut,
         seeds = [
             b"vault".as_ref(),
+            vault.load()?.pool_id.as_ref(),
         ],
         bump = vault.load()?.bump
     )]
     pub vault: AccountLoader<'info, Vault>,
 
+    #[account(
+        mut,
+        seeds = [
+            b"fee_treasury".as_ref(),
+            vault.key().as_ref()
+        ],
+        bump = vault.load()?.fee_treasury_bump
+    )]
+    pub fee_treasury: SystemAccount<'info>,
+
+    // Token account holding the vault's pass NFT, only read when a pass mint is set.
+    /// CHECK:
+    pub pass_token_account: AccountInfo<'info>,
+
+    // The staker's and the fee treasury's `fee_mint` token accounts, only used when fees are charged in tokens.
+    /// CHECK:
+    #[account(mut)]
+    pub staker_fee_account: AccountInfo<'info>,
+
+    /// CHECK:
+    #[account(mut)]
+    pub fee_treasury_fee_account: AccountInfo<'info>,
 
     // The Mint Account for the NFT.
     pub token_mint: Account<'info, Mint>,
 
+    #[account(
+        init,
+        payer = staker,
+        seeds = [
+            b"receipt".as_ref(),
+            vault.key().as_ref(),
+            token_mint.key().as_ref()
+        ],
+        bump,
+        space = StakeReceipt::LEN + 8
+    )]
+    pub receipt: Account<'info, StakeReceipt>,
+
     // The Token Account holding the NFT.
     #[account(
         mut,
@@ -150,6 +489,7 @@ pub struct Unstake<'info> {
         mut,
         seeds = [
             b"user".as_ref(),
+            vault.key().as_ref(),
             staker.key().as_ref()
         ],
         bump = user.bump
@@ -160,29 +500,63 @@ pub struct Unstake<'info> {
         mut,
         seeds = [
             b"vault".as_ref(),
+            vault.load()?.pool_id.as_ref(),
         ],
         bump = vault.load()?.bump
     )]
     pub vault: AccountLoader<'info, Vault>,
 
-    pub token_mint: Account<'info, Mint>,
-
-    pub booster_mint: Account<'info, Mint>,
-
-    // The Token Account holding the NFT.
     #[account(
         mut,
-        associated_token::mint = token_mint,
-        associated_token::authority = staker,
+        seeds = [
+            b"fee_treasury".as_ref(),
+            vault.key().as_ref()
+        ],
+        bump = vault.load()?.fee_treasury_bump
     )]
-    pub staker_ata: Box<Account<'info, TokenAccount>>,
+    pub fee_treasury: SystemAccount<'info>,
 
+    // Token account holding the vault's pass NFT, only read when a pass mint is set.
     /// CHECK:
-    pub edition: AccountInfo<'info>,
+    pub pass_token_account: AccountInfo<'info>,
 
-    pub system_program: Program<'info, System>,
+    // The staker's and the fee treasury's `fee_mint` token accounts, only used when fees are charged in tokens.
+    /// CHECK:
+    #[account(mut)]
+    pub staker_fee_account: AccountInfo<'info>,
 
-    pub token_program: Program<'info, Token>,
+    /// CHECK:
+    #[account(mut)]
+    pub fee_treasury_fee_account: AccountInfo<'info>,
+
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        close = staker,
+        seeds = [
+            b"receipt".as_ref(),
+            vault.key().as_ref(),
+            token_mint.key().as_ref()
+        ],
+        bump = receipt.bump
+    )]
+    pub receipt: Account<'info, StakeReceipt>,
+
+    // The Token Account holding the NFT.
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = staker,
+    )]
+    pub staker_ata: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK:
+    pub edition: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
 
     pub rent: Sysvar<'info, Rent>,
     // the token metadata program
@@ -191,6 +565,355 @@ pub struct Unstake<'info> {
     pub token_metadata_program: AccountInfo<'info>,
 }
 
+#[derive(Accounts)]
+pub struct EmergencyUnstake<'info> {
+    #[account(mut)]
+    pub staker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"user".as_ref(),
+            vault.key().as_ref(),
+            staker.key().as_ref()
+        ],
+        bump = user.bump
+    )]
+    pub user: Account<'info, User>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vault".as_ref(),
+            vault.load()?.pool_id.as_ref(),
+        ],
+        bump = vault.load()?.bump
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    // The unstake fee is still charged outside emergency mode.
+    #[account(
+        mut,
+        seeds = [
+            b"fee_treasury".as_ref(),
+            vault.key().as_ref()
+        ],
+        bump = vault.load()?.fee_treasury_bump
+    )]
+    pub fee_treasury: SystemAccount<'info>,
+
+    // Token account holding the vault's pass NFT, only read when a pass mint is set.
+    /// CHECK:
+    pub pass_token_account: AccountInfo<'info>,
+
+    // The staker's and the fee treasury's `fee_mint` token accounts, only used when fees are charged in tokens.
+    /// CHECK:
+    #[account(mut)]
+    pub staker_fee_account: AccountInfo<'info>,
+
+    /// CHECK:
+    #[account(mut)]
+    pub fee_treasury_fee_account: AccountInfo<'info>,
+
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        close = staker,
+        seeds = [
+            b"receipt".as_ref(),
+            vault.key().as_ref(),
+            token_mint.key().as_ref()
+        ],
+        bump = receipt.bump
+    )]
+    pub receipt: Account<'info, StakeReceipt>,
+
+    // The Token Account holding the NFT.
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = staker,
+    )]
+    pub staker_ata: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK:
+    pub edition: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
+    // the token metadata program
+    /// CHECK:
+    #[account(constraint = token_metadata_program.key == &metaplex_token_metadata::ID)]
+    pub token_metadata_program: AccountInfo<'info>,
+}
+
+// Each NFT passes `token_mint`, `staker_ata`, `nft_metadata_account`, `edition`
+// and its receipt PDA, in that order, through the remaining accounts.
+#[derive(Accounts)]
+pub struct StakeMany<'info> {
+    #[account(mut)]
+    pub staker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"user".as_ref(),
+            vault.key().as_ref(),
+            staker.key().as_ref()
+        ],
+        bump = user.bump
+    )]
+    pub user: Account<'info, User>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vault".as_ref(),
+            vault.load()?.pool_id.as_ref(),
+        ],
+        bump = vault.load()?.bump
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"fee_treasury".as_ref(),
+            vault.key().as_ref()
+        ],
+        bump = vault.load()?.fee_treasury_bump
+    )]
+    pub fee_treasury: SystemAccount<'info>,
+
+    // Token account holding the vault's pass NFT, only read when a pass mint is set.
+    /// CHECK:
+    pub pass_token_account: AccountInfo<'info>,
+
+    // The staker's and the fee treasury's `fee_mint` token accounts, only used when fees are charged in tokens.
+    /// CHECK:
+    #[account(mut)]
+    pub staker_fee_account: AccountInfo<'info>,
+
+    /// CHECK:
+    #[account(mut)]
+    pub fee_treasury_fee_account: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
+    // the token metadata program
+    /// CHECK:
+    #[account(constraint = token_metadata_program.key == &metaplex_token_metadata::ID)]
+    pub token_metadata_program: AccountInfo<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+// Each NFT passes `token_mint`, `staker_ata`, `edition` and its receipt PDA,
+// in that order, through the remaining accounts.
+#[derive(Accounts)]
+pub struct UnstakeMany<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(mut)]
+    pub staker: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"user".as_ref(),
+            vault.key().as_ref(),
+            staker.key().as_ref()
+        ],
+        bump = user.bump
+    )]
+    pub user: Account<'info, User>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vault".as_ref(),
+            vault.load()?.pool_id.as_ref(),
+        ],
+        bump = vault.load()?.bump
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"fee_treasury".as_ref(),
+            vault.key().as_ref()
+        ],
+        bump = vault.load()?.fee_treasury_bump
+    )]
+    pub fee_treasury: SystemAccount<'info>,
+
+    // Token account holding the vault's pass NFT, only read when a pass mint is set.
+    /// CHECK:
+    pub pass_token_account: AccountInfo<'info>,
+
+    // The staker's and the fee treasury's `fee_mint` token accounts, only used when fees are charged in tokens.
+    /// CHECK:
+    #[account(mut)]
+    pub staker_fee_account: AccountInfo<'info>,
+
+    /// CHECK:
+    #[account(mut)]
+    pub fee_treasury_fee_account: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
+    // the token metadata program
+    /// CHECK:
+    #[account(constraint = token_metadata_program.key == &metaplex_token_metadata::ID)]
+    pub token_metadata_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct StakeBooster<'info> {
+    #[account(mut)]
+    pub staker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"user".as_ref(),
+            vault.key().as_ref(),
+            staker.key().as_ref()
+        ],
+        bump = user.bump
+    )]
+    pub user: Account<'info, User>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vault".as_ref(),
+            vault.load()?.pool_id.as_ref(),
+        ],
+        bump = vault.load()?.bump
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    pub booster_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = booster_mint,
+        associated_token::authority = staker,
+    )]
+    pub staker_ata: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK:
+    pub nft_metadata_account: AccountInfo<'info>,
+
+    /// CHECK:
+    pub edition: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+    // the token metadata program
+    /// CHECK:
+    #[account(constraint = token_metadata_program.key == &metaplex_token_metadata::ID)]
+    pub token_metadata_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UnstakeBooster<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(mut)]
+    pub staker: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"user".as_ref(),
+            vault.key().as_ref(),
+            staker.key().as_ref()
+        ],
+        bump = user.bump
+    )]
+    pub user: Account<'info, User>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vault".as_ref(),
+            vault.load()?.pool_id.as_ref(),
+        ],
+        bump = vault.load()?.bump
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    pub booster_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = booster_mint,
+        associated_token::authority = staker,
+    )]
+    pub staker_ata: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK:
+    pub edition: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+    // the token metadata program
+    /// CHECK:
+    #[account(constraint = token_metadata_program.key == &metaplex_token_metadata::ID)]
+    pub token_metadata_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct EmergencyUnstakeBooster<'info> {
+    #[account(mut)]
+    pub staker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"user".as_ref(),
+            vault.key().as_ref(),
+            staker.key().as_ref()
+        ],
+        bump = user.bump
+    )]
+    pub user: Account<'info, User>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vault".as_ref(),
+            vault.load()?.pool_id.as_ref(),
+        ],
+        bump = vault.load()?.bump
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    pub booster_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = booster_mint,
+        associated_token::authority = staker,
+    )]
+    pub staker_ata: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK:
+    pub edition: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+    // the token metadata program
+    /// CHECK:
+    #[account(constraint = token_metadata_program.key == &metaplex_token_metadata::ID)]
+    pub token_metadata_program: AccountInfo<'info>,
+}
 
 #[derive(Accounts)]
 pub struct Claim<'info> {
@@ -204,6 +927,7 @@ pub struct Claim<'info> {
         mut,
         seeds = [
             b"user".as_ref(),
+            vault.key().as_ref(),
             staker.key().as_ref()
         ],
         bump = user.bump
@@ -214,10 +938,189 @@ pub struct Claim<'info> {
         mut,
         seeds = [
             b"vault".as_ref(),
+            vault.load()?.pool_id.as_ref(),
         ],
         bump = vault.load()?.bump
     )]
     pub vault: AccountLoader<'info, Vault>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"fee_treasury".as_ref(),
+            vault.key().as_ref()
+        ],
+        bump = vault.load()?.fee_treasury_bump
+    )]
+    pub fee_treasury: SystemAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimToken<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(mut)]
+    pub staker: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"user".as_ref(),
+            vault.key().as_ref(),
+            staker.key().as_ref()
+        ],
+        bump = user.bump
+    )]
+    pub user: Account<'info, User>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vault".as_ref(),
+            vault.load()?.pool_id.as_ref(),
+        ],
+        bump = vault.load()?.bump
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    #[account(
+        seeds = [
+            b"fee_treasury".as_ref(),
+            vault.key().as_ref()
+        ],
+        bump = vault.load()?.fee_treasury_bump
+    )]
+    pub fee_treasury: SystemAccount<'info>,
+
+    #[account(address = vault.load()?.reward_mint)]
+    pub reward_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = vault,
+    )]
+    pub vault_reward_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = reward_mint,
+        associated_token::authority = staker,
+    )]
+    pub staker_reward_account: Box<Account<'info, TokenAccount>>,
+
+    // The fee treasury's token account collecting claim fees, created with the vault's reward account.
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = fee_treasury,
+    )]
+    pub fee_treasury_reward_account: Box<Account<'info, TokenAccount>>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(index: u64)]
+pub struct CreateProposal<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vault".as_ref(),
+            vault.load()?.pool_id.as_ref(),
+        ],
+        bump = vault.load()?.bump
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    #[account(
+        init,
+        payer = proposer,
+        seeds = [
+            b"proposal".as_ref(),
+            vault.key().as_ref(),
+            index.to_le_bytes().as_ref()
+        ],
+        bump,
+        space = Proposal::LEN + 8
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveProposal<'info> {
+    pub approver: Signer<'info>,
+
+    #[account(
+        seeds = [
+            b"vault".as_ref(),
+            vault.load()?.pool_id.as_ref(),
+        ],
+        bump = vault.load()?.bump
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"proposal".as_ref(),
+            vault.key().as_ref(),
+            proposal.index.to_le_bytes().as_ref()
+        ],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    pub executor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vault".as_ref(),
+            vault.load()?.pool_id.as_ref(),
+        ],
+        bump = vault.load()?.bump
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"proposal".as_ref(),
+            vault.key().as_ref(),
+            proposal.index.to_le_bytes().as_ref()
+        ],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    // Receives drained funds, checked against the proposal in the handler. Unused by other actions.
+    /// CHECK:
+    #[account(mut)]
+    pub recipient: AccountInfo<'info>,
+
+    // The vault's reward token account, only used to drain token rewards.
+    /// CHECK:
+    #[account(mut)]
+    pub vault_reward_account: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
 }
 
 #[derive(Accounts)]
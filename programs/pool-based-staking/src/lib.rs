@@ -1,10 +1,13 @@
 mod ins;
 mod state;
 mod errors;
+mod events;
 mod user;
 mod stake;
 mod vault;
 mod constants;
+mod merkle;
+mod multisig;
 
 use anchor_lang::prelude::*;
 
@@ -12,6 +15,7 @@ use crate::ins::*;
 use crate::vault::*;
 use crate::user::*;
 use crate::stake::*;
+use crate::multisig::*;
 use crate::state::*;
 
 declare_id!("G2dDyhgXAFNAHvAn3VtLhTQvDEqDxBsDRooka5GERtYb");
@@ -26,9 +30,12 @@ pub mod pool_based_staking {
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn initialize_vault(
         ctx: Context<InitializeVault>,
+        pool_id: Pubkey,
         creator_address: Pubkey,
+        collection_mint: Pubkey,
         payout_interval: u64,
         payout_amount: u64,
         stake_fee: u64,
@@ -36,7 +43,9 @@ pub mod pool_based_staking {
     ) -> Result<()> {
         handle_vault_initialization(
             ctx,
+            pool_id,
             creator_address,
+            collection_mint,
             payout_interval,
             payout_amount,
             stake_fee,
@@ -44,24 +53,60 @@ pub mod pool_based_staking {
         )
     }
 
-    pub fn update_vault(
+    pub fn set_fees(ctx: Context<UpdateVault>, stake_fee: u64, unstake_fee: u64) -> Result<()> {
+        handle_set_fees(ctx, stake_fee, unstake_fee)
+    }
+
+    pub fn set_payout_params(
         ctx: Context<UpdateVault>,
-        new_authority: Pubkey,
-        creator_address: Pubkey,
         payout_interval: u64,
         payout_amount: u64,
-        stake_fee: u64,
-        unstake_fee: u64,
     ) -> Result<()> {
-        handle_vault_update(
-            ctx,
-            new_authority,
-            creator_address,
-            payout_interval,
-            payout_amount,
-            stake_fee,
-            unstake_fee,
-        )
+        handle_set_payout_params(ctx, payout_interval, payout_amount)
+    }
+
+    pub fn set_timelock_delay(ctx: Context<UpdateVault>, timelock_delay: u64) -> Result<()> {
+        handle_set_timelock_delay(ctx, timelock_delay)
+    }
+
+    pub fn execute_pending_changes(ctx: Context<ExecutePendingChanges>) -> Result<()> {
+        handle_execute_pending_changes(ctx)
+    }
+
+    pub fn cancel_pending_changes(ctx: Context<UpdateVault>) -> Result<()> {
+        handle_cancel_pending_changes(ctx)
+    }
+
+    pub fn set_creator(
+        ctx: Context<UpdateVault>,
+        creator_address: Pubkey,
+        collection_mint: Pubkey,
+    ) -> Result<()> {
+        handle_set_creator(ctx, creator_address, collection_mint)
+    }
+
+    pub fn propose_authority(ctx: Context<UpdateVault>, new_authority: Pubkey) -> Result<()> {
+        handle_propose_authority(ctx, new_authority)
+    }
+
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        handle_accept_authority(ctx)
+    }
+
+    pub fn set_roles(ctx: Context<UpdateVault>, operator: Pubkey, treasurer: Pubkey) -> Result<()> {
+        handle_set_roles(ctx, operator, treasurer)
+    }
+
+    pub fn pause(ctx: Context<UpdateVault>, flags: u8) -> Result<()> {
+        handle_pause(ctx, flags)
+    }
+
+    pub fn unpause(ctx: Context<UpdateVault>, flags: u8) -> Result<()> {
+        handle_unpause(ctx, flags)
+    }
+
+    pub fn set_emergency_mode(ctx: Context<UpdateVault>, enabled: bool) -> Result<()> {
+        handle_set_emergency_mode(ctx, enabled)
     }
 
     pub fn fund(ctx: Context<FundSolVault>, amount: u64) -> Result<()> {
@@ -72,26 +117,158 @@ pub mod pool_based_staking {
         handle_drain(ctx, amount)
     }
 
+    pub fn withdraw_fees(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
+        handle_withdraw_fees(ctx, amount)
+    }
+
+    pub fn initialize_fee_token_account(ctx: Context<InitializeFeeTokenAccount>) -> Result<()> {
+        handle_initialize_fee_token_account(ctx)
+    }
+
+    pub fn withdraw_fee_tokens(ctx: Context<WithdrawFeeTokens>, amount: u64) -> Result<()> {
+        handle_withdraw_fee_tokens(ctx, amount)
+    }
+
+    pub fn initialize_reward_token_account(ctx: Context<InitializeRewardTokenAccount>) -> Result<()> {
+        handle_initialize_reward_token_account(ctx)
+    }
+
+    pub fn fund_token(ctx: Context<FundTokenVault>, amount: u64) -> Result<()> {
+        handle_fund_token(ctx, amount)
+    }
+
+    pub fn drain_token(ctx: Context<DrainTokenVault>, amount: u64) -> Result<()> {
+        handle_drain_token(ctx, amount)
+    }
+
+    pub fn update_weights_root(ctx: Context<UpdateVault>, weights_root: [u8; 32]) -> Result<()> {
+        handle_update_weights_root(ctx, weights_root)
+    }
+
+    pub fn update_allowlist_root(ctx: Context<UpdateVault>, allowlist_root: [u8; 32]) -> Result<()> {
+        handle_update_allowlist_root(ctx, allowlist_root)
+    }
+
+    pub fn set_lock_tier(
+        ctx: Context<UpdateVault>,
+        index: u8,
+        duration: u64,
+        multiplier_bps: u64,
+    ) -> Result<()> {
+        handle_set_lock_tier(ctx, index, duration, multiplier_bps)
+    }
+
+    pub fn set_booster(
+        ctx: Context<UpdateVault>,
+        index: u8,
+        key: Pubkey,
+        multiplier_bps: u64,
+    ) -> Result<()> {
+        handle_set_booster(ctx, index, key, multiplier_bps)
+    }
+
+    pub fn set_batch_fee_mode(ctx: Context<UpdateVault>, charge_once: bool) -> Result<()> {
+        handle_set_batch_fee_mode(ctx, charge_once)
+    }
+
+    pub fn set_fee_config(
+        ctx: Context<UpdateVault>,
+        fee_mint: Pubkey,
+        pass_mint: Pubkey,
+        pass_discount_bps: u64,
+    ) -> Result<()> {
+        handle_set_fee_config(ctx, fee_mint, pass_mint, pass_discount_bps)
+    }
+
+    pub fn set_claim_fee(ctx: Context<UpdateVault>, claim_fee_bps: u64) -> Result<()> {
+        handle_set_claim_fee(ctx, claim_fee_bps)
+    }
+
+    pub fn set_emission_mode(ctx: Context<UpdateVault>, emission_mode: u8) -> Result<()> {
+        handle_set_emission_mode(ctx, emission_mode)
+    }
+
+    pub fn set_emission_end_time(ctx: Context<UpdateVault>, emission_end_time: u64) -> Result<()> {
+        handle_set_emission_end_time(ctx, emission_end_time)
+    }
+
+    pub fn get_reward_runway(ctx: Context<ViewVault>) -> Result<RewardRunway> {
+        handle_get_reward_runway(ctx)
+    }
+
     pub fn start_payout_schedule(ctx: Context<UpdateVault>) -> Result<()> {
         handle_start_payout_schedule(ctx)
     }
 
+    pub fn set_multisig(ctx: Context<UpdateVault>, signers: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        handle_set_multisig(ctx, signers, threshold)
+    }
+
+    pub fn create_proposal(ctx: Context<CreateProposal>, index: u64, action: ProposalAction) -> Result<()> {
+        handle_create_proposal(ctx, index, action)
+    }
+
+    pub fn approve_proposal(ctx: Context<ApproveProposal>) -> Result<()> {
+        handle_approve_proposal(ctx)
+    }
+
+    pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
+        handle_execute_proposal(ctx)
+    }
+
     pub fn create_stake_account(ctx: Context<CreateStakeAccount>) -> Result<()> {
         handle_create_stake_account(ctx)
     }
 
-    pub fn stake(ctx: Context<Stake>) -> Result<()> {
-        handle_stake(ctx)
+    pub fn stake(
+        ctx: Context<Stake>,
+        weight: u64,
+        weight_proof: Vec<[u8; 32]>,
+        allowlist_proof: Vec<[u8; 32]>,
+        lock_tier: Option<u8>,
+    ) -> Result<()> {
+        handle_stake(ctx, weight, weight_proof, allowlist_proof, lock_tier)
     }
 
     pub fn unstake(ctx: Context<Unstake>) -> Result<()> {
         handle_unstake(ctx)
     }
 
+    pub fn stake_many<'info>(
+        ctx: Context<'_, '_, '_, 'info, StakeMany<'info>>,
+        items: Vec<StakeItem>,
+    ) -> Result<()> {
+        handle_stake_many(ctx, items)
+    }
+
+    pub fn unstake_many<'info>(ctx: Context<'_, '_, '_, 'info, UnstakeMany<'info>>) -> Result<()> {
+        handle_unstake_many(ctx)
+    }
+
+    pub fn emergency_unstake(ctx: Context<EmergencyUnstake>) -> Result<()> {
+        handle_emergency_unstake(ctx)
+    }
+
+    pub fn emergency_unstake_booster(ctx: Context<EmergencyUnstakeBooster>) -> Result<()> {
+        handle_emergency_unstake_booster(ctx)
+    }
+
+    pub fn stake_booster(ctx: Context<StakeBooster>) -> Result<()> {
+        handle_stake_booster(ctx)
+    }
+
+    pub fn unstake_booster(ctx: Context<UnstakeBooster>) -> Result<()> {
+        handle_unstake_booster(ctx)
+    }
+
     pub fn claim(ctx: Context<Claim>) -> Result<()> {
         handle_claim_rewards(ctx)
     }
 
+    pub fn claim_token(ctx: Context<ClaimToken>) -> Result<()> {
+        handle_claim_token_rewards(ctx)
+    }
+
     pub fn close_pda(ctx: Context<ClosePda>) -> Result<()> {
         let dest_account_info = ctx.accounts.signer.to_account_info();
         let source_account_info = ctx.accounts.pda.to_account_info();
@@ -0,0 +1,15 @@
+use anchor_lang::solana_program::keccak;
+
+// Verifies a proof against a tree built with sorted keccak pairs, as produced by merkletreejs with `sortPairs`.
+pub fn verify_proof(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+  let mut computed_hash = leaf;
+  for proof_element in proof.iter() {
+    computed_hash = if computed_hash <= *proof_element {
+      keccak::hashv(&[&computed_hash, proof_element]).0
+    } else {
+      keccak::hashv(&[proof_element, &computed_hash]).0
+    };
+  }
+
+  computed_hash == root
+}
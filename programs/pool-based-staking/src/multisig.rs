@@ -0,0 +1,324 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address;
+
+use crate::constants::*;
+use crate::errors::*;
+use crate::events::*;
+use crate::ins::*;
+use crate::state::*;
+
+// Drains the vault on behalf of an approved proposal.
+fn execute_drain(ctx: &Context<ExecuteProposal>, amount: u64, recipient: Pubkey) -> Result<()> {
+  require_keys_eq!(ctx.accounts.recipient.key(), recipient, CustomError::AccountMismatch);
+
+  let vault_key = ctx.accounts.vault.key();
+  let (reward_mint, total_amount, token_vault_bump, pool_id) = {
+    let vault = &mut ctx.accounts.vault.load_mut()?;
+    vault.record_drain(amount)?;
+
+    if !vault.pays_token_rewards() {
+      let vault_info = ctx.accounts.vault.to_account_info();
+      let rent_exempt_minimum = Rent::get()?.minimum_balance(vault_info.data_len());
+      let remaining_lamports = vault_info.lamports().checked_sub(amount).ok_or(CustomError::InsufficientVaultBalance)?;
+      require!(
+        remaining_lamports >= vault.required_lamports(rent_exempt_minimum)?,
+        CustomError::InsufficientVaultBalance
+      );
+      **vault_info.try_borrow_mut_lamports()? -= amount;
+      **ctx.accounts.recipient.try_borrow_mut_lamports()? += amount;
+    }
+
+    (vault.reward_mint, vault.total_amount, vault.bump, vault.pool_id)
+  };
+
+  if reward_mint != Pubkey::default() {
+    require_keys_eq!(
+      ctx.accounts.vault_reward_account.key(),
+      get_associated_token_address(&vault_key, &reward_mint),
+      CustomError::AccountMismatch
+    );
+
+    let seeds = &[
+      b"vault".as_ref(),
+      pool_id.as_ref(),
+      &[token_vault_bump],
+    ];
+    let signer = &[&seeds[..]];
+    let cpi_context = CpiContext::new_with_signer(
+      ctx.accounts.token_program.to_account_info(),
+      anchor_spl::token::Transfer {
+        from: ctx.accounts.vault_reward_account.to_account_info(),
+        to: ctx.accounts.recipient.to_account_info(),
+        authority: ctx.accounts.vault.to_account_info(),
+      },
+      signer
+    );
+    anchor_spl::token::transfer(cpi_context, amount)?;
+  }
+
+  emit!(Drained {
+    vault: vault_key,
+    authority: ctx.accounts.executor.key(),
+    mint: reward_mint,
+    amount,
+    total_amount,
+    timestamp: current_timestamp()? as i64,
+  });
+
+  Ok(())
+}
+
+// The vault authority registers the first signer set, later changes go through a proposal.
+pub fn handle_set_multisig(ctx: Context<UpdateVault>, signers: Vec<Pubkey>, threshold: u8) -> Result<()> {
+  require_keys_eq!(
+    ctx.accounts.vault.load()?.authority,
+    ctx.accounts.authority.key(),
+    CustomError::Unauthorized
+  );
+  let vault = &mut ctx.accounts.vault.load_mut()?;
+  require!(!vault.multisig_enabled(), CustomError::MultisigRequired);
+  require!(threshold > 0, CustomError::InvalidMultisig);
+
+  vault.set_multisig(&signers, threshold)?;
+
+  emit!(MultisigUpdated {
+    vault: ctx.accounts.vault.key(),
+    signers: vault.multisig_signers,
+    threshold,
+    timestamp: current_timestamp()? as i64,
+  });
+
+  Ok(())
+}
+
+pub fn handle_create_proposal(ctx: Context<CreateProposal>, index: u64, action: ProposalAction) -> Result<()> {
+  let vault = &mut ctx.accounts.vault.load_mut()?;
+  require!(vault.is_multisig_signer(&ctx.accounts.proposer.key()), CustomError::NotMultisigSigner);
+  require_eq!(index, vault.proposal_count, CustomError::InvalidProposalIndex);
+  vault.proposal_count = vault.proposal_count.checked_add(1).ok_or(CustomError::MathOverflow)?;
+
+  let now = current_timestamp()?;
+  let proposal = &mut ctx.accounts.proposal;
+  proposal.vault = ctx.accounts.vault.key();
+  proposal.index = index;
+  proposal.proposer = ctx.accounts.proposer.key();
+  proposal.action = action;
+  proposal.approvers = [Pubkey::default(); MAX_MULTISIG_SIGNERS];
+  proposal.created_at = now;
+  proposal.executed = false;
+  proposal.bump = *ctx.bumps.get("proposal").unwrap();
+
+  // Creating a proposal counts as the proposer's approval.
+  proposal.approve(ctx.accounts.proposer.key())?;
+
+  emit!(ProposalCreated {
+    vault: proposal.vault,
+    proposal: proposal.key(),
+    index,
+    proposer: proposal.proposer,
+    action,
+    timestamp: now as i64,
+  });
+
+  Ok(())
+}
+
+pub fn handle_approve_proposal(ctx: Context<ApproveProposal>) -> Result<()> {
+  let vault = ctx.accounts.vault.load()?;
+  let proposal = &mut ctx.accounts.proposal;
+  require!(vault.is_multisig_signer(&ctx.accounts.approver.key()), CustomError::NotMultisigSigner);
+  require!(!proposal.executed, CustomError::ProposalExecuted);
+
+  proposal.approve(ctx.accounts.approver.key())?;
+
+  emit!(ProposalApproved {
+    vault: ctx.accounts.vault.key(),
+    proposal: proposal.key(),
+    approver: ctx.accounts.approver.key(),
+    approvals: proposal.approval_count(&vault),
+    timestamp: current_timestamp()? as i64,
+  });
+
+  Ok(())
+}
+
+// Anyone can execute a proposal once it has enough approvals.
+pub fn handle_execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
+  let action = ctx.accounts.proposal.action;
+  {
+    let vault = ctx.accounts.vault.load()?;
+    require!(!ctx.accounts.proposal.executed, CustomError::ProposalExecuted);
+    require!(vault.multisig_enabled(), CustomError::InvalidMultisig);
+    require!(
+      ctx.accounts.proposal.approval_count(&vault) >= vault.multisig_threshold,
+      CustomError::ThresholdNotMet
+    );
+  }
+  ctx.accounts.proposal.executed = true;
+
+  let vault_key = ctx.accounts.vault.key();
+  let timestamp = current_timestamp()? as i64;
+  match action {
+    ProposalAction::Drain { amount, recipient } => {
+      // A queued drain leaves the proposal open so it can be executed again once the timelock has passed.
+      let queued_eta = {
+        let vault = &mut ctx.accounts.vault.load_mut()?;
+        if vault.take_drain(amount, timestamp as u64)? { None } else { Some(vault.pending_drain.eta) }
+      };
+      if let Some(eta) = queued_eta {
+        ctx.accounts.proposal.executed = false;
+        emit!(DrainQueued {
+          vault: vault_key,
+          authority: ctx.accounts.executor.key(),
+          amount,
+          eta,
+          timestamp,
+        });
+        return Ok(());
+      }
+      execute_drain(&ctx, amount, recipient)?;
+    }
+    ProposalAction::SetAuthority { new_authority } => {
+      let vault = &mut ctx.accounts.vault.load_mut()?;
+      vault.pending_authority = new_authority;
+
+      emit!(AuthorityProposed {
+        vault: vault_key,
+        authority: vault.authority,
+        pending_authority: new_authority,
+        timestamp,
+      });
+    }
+    ProposalAction::SetFees { stake_fee, unstake_fee } => {
+      require!(stake_fee <= MAX_FEE && unstake_fee <= MAX_FEE, CustomError::InvalidFee);
+      let vault = &mut ctx.accounts.vault.load_mut()?;
+      vault.queue_fees(stake_fee, unstake_fee, timestamp as u64)?;
+
+      emit!(VaultUpdated {
+        vault: vault_key,
+        authority: ctx.accounts.executor.key(),
+        change: VaultChange::Fees { stake_fee, unstake_fee },
+        timestamp,
+      });
+    }
+    ProposalAction::SetPayoutParams { payout_interval, payout_amount } => {
+      require!(payout_interval > 0, CustomError::InvalidInterval);
+      let vault = &mut ctx.accounts.vault.load_mut()?;
+      vault.queue_payout_params(payout_interval, payout_amount, timestamp as u64)?;
+
+      emit!(VaultUpdated {
+        vault: vault_key,
+        authority: ctx.accounts.executor.key(),
+        change: VaultChange::PayoutParams { payout_interval, payout_amount },
+        timestamp,
+      });
+    }
+    ProposalAction::SetMultisig { signers, threshold } => {
+      let signers: Vec<Pubkey> = signers.iter()
+        .filter(|signer| **signer != Pubkey::default())
+        .copied()
+        .collect();
+      let vault = &mut ctx.accounts.vault.load_mut()?;
+      vault.set_multisig(&signers, threshold)?;
+
+      emit!(MultisigUpdated {
+        vault: vault_key,
+        signers: vault.multisig_signers,
+        threshold,
+        timestamp,
+      });
+    }
+    ProposalAction::SetRoles { operator, treasurer } => {
+      let vault = &mut ctx.accounts.vault.load_mut()?;
+      vault.operator = operator;
+      vault.treasurer = treasurer;
+
+      emit!(RolesUpdated {
+        vault: vault_key,
+        authority: vault.authority,
+        operator,
+        treasurer,
+        timestamp,
+      });
+    }
+    ProposalAction::SetFeeConfig { fee_mint, pass_mint, pass_discount_bps } => {
+      require!(pass_discount_bps <= BPS_DENOMINATOR, CustomError::InvalidDiscount);
+      let vault = &mut ctx.accounts.vault.load_mut()?;
+      vault.queue_fee_config(fee_mint, pass_mint, pass_discount_bps, timestamp as u64)?;
+
+      emit!(VaultUpdated {
+        vault: vault_key,
+        authority: ctx.accounts.executor.key(),
+        change: VaultChange::FeeConfig { fee_mint, pass_mint, pass_discount_bps },
+        timestamp,
+      });
+    }
+    ProposalAction::SetClaimFee { claim_fee_bps } => {
+      require!(claim_fee_bps <= MAX_CLAIM_FEE_BPS, CustomError::InvalidFee);
+      let vault = &mut ctx.accounts.vault.load_mut()?;
+      vault.queue_claim_fee(claim_fee_bps, timestamp as u64)?;
+
+      emit!(VaultUpdated {
+        vault: vault_key,
+        authority: ctx.accounts.executor.key(),
+        change: VaultChange::ClaimFee { claim_fee_bps },
+        timestamp,
+      });
+    }
+    ProposalAction::SetBatchFeeMode { charge_once } => {
+      let vault = &mut ctx.accounts.vault.load_mut()?;
+      vault.queue_batch_fee_mode(charge_once as u8, timestamp as u64)?;
+
+      emit!(VaultUpdated {
+        vault: vault_key,
+        authority: ctx.accounts.executor.key(),
+        change: VaultChange::BatchFeeMode { charge_once },
+        timestamp,
+      });
+    }
+    ProposalAction::SetEmissionEndTime { emission_end_time } => {
+      let vault = &mut ctx.accounts.vault.load_mut()?;
+      vault.queue_emission_end_time(emission_end_time, timestamp as u64)?;
+
+      emit!(VaultUpdated {
+        vault: vault_key,
+        authority: ctx.accounts.executor.key(),
+        change: VaultChange::EmissionEndTime { emission_end_time },
+        timestamp,
+      });
+    }
+    ProposalAction::SetTimelockDelay { timelock_delay } => {
+      require!(timelock_delay <= MAX_TIMELOCK_DELAY, CustomError::InvalidTimelockDelay);
+      let vault = &mut ctx.accounts.vault.load_mut()?;
+      vault.queue_timelock_delay(timelock_delay, timestamp as u64)?;
+
+      emit!(VaultUpdated {
+        vault: vault_key,
+        authority: ctx.accounts.executor.key(),
+        change: VaultChange::TimelockDelay { timelock_delay },
+        timestamp,
+      });
+    }
+    ProposalAction::CancelPendingChanges => {
+      let vault = &mut ctx.accounts.vault.load_mut()?;
+      vault.cancel_pending_changes();
+
+      emit!(VaultUpdated {
+        vault: vault_key,
+        authority: ctx.accounts.executor.key(),
+        change: VaultChange::PendingChangesCancelled,
+        timestamp,
+      });
+    }
+  }
+
+  emit!(ProposalExecuted {
+    vault: vault_key,
+    proposal: ctx.accounts.proposal.key(),
+    executor: ctx.accounts.executor.key(),
+    action,
+    timestamp,
+  });
+
+  Ok(())
+}
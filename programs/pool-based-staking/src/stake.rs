@@ -1,90 +1,410 @@
 use anchor_lang::prelude::*;
+use anchor_lang::AccountsClose;
 use anchor_lang::solana_program::{
+  keccak,
   program::{invoke, invoke_signed}
 };
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::{Mint, Token, TokenAccount};
 use mpl_token_metadata::instruction::{freeze_delegated_account, thaw_delegated_account};
+use mpl_token_metadata::state::{Metadata, TokenMetadataAccount};
 
+use crate::constants::*;
 use crate::errors::*;
+use crate::events::*;
 use crate::ins::*;
+use crate::merkle::*;
+use crate::state::*;
+
+fn is_collection_nft(vault: &Vault, metadata: &Metadata) -> bool {
+  if vault.collection_mint != Pubkey::default() {
+    if let Some(collection) = &metadata.collection {
+      if collection.verified && collection.key == vault.collection_mint {
+        return true;
+      }
+    }
+  }
+
+  match &metadata.data.creators {
+    Some(creators) => creators
+      .iter()
+      .any(|creator| creator.verified && creator.address == vault.creator_address),
+    None => false,
+  }
+}
+
+fn find_booster(vault: &Vault, metadata: &Metadata) -> Option<u64> {
+  vault.boosters.iter()
+    .filter(|booster| booster.key != Pubkey::default())
+    .find(|booster| {
+      booster.key == metadata.mint || match &metadata.collection {
+        Some(collection) => collection.verified && collection.key == booster.key,
+        None => false,
+      }
+    })
+    .map(|booster| booster.multiplier_bps)
+}
+
+// Delegates the NFT to the vault and freezes it in the owner's wallet.
+#[allow(clippy::too_many_arguments)]
+fn freeze_nft<'info>(
+  token_program: &Program<'info, Token>,
+  token_metadata_program: &AccountInfo<'info>,
+  vault: &AccountInfo<'info>,
+  owner: &AccountInfo<'info>,
+  token_account: &AccountInfo<'info>,
+  edition: &AccountInfo<'info>,
+  mint: &AccountInfo<'info>,
+  seeds: &[&[u8]],
+) -> Result<()> {
+  let cpi_context = CpiContext::new(
+    token_program.to_account_info(),
+    anchor_spl::token::Approve {
+        to: token_account.clone(),
+        delegate: vault.clone(),
+        authority: owner.clone()
+    }
+  );
+
+  anchor_spl::token::approve(cpi_context, 1)?;
+
+  invoke_signed(
+      &freeze_delegated_account(
+          token_metadata_program.key(),
+          vault.key(),
+          token_account.key(),
+          edition.key(),
+          mint.key(),
+      ),
+      &[
+          vault.clone(),
+          token_account.clone(),
+          edition.clone(),
+          mint.clone()
+      ],
+      &[seeds]
+  )?;
+
+  Ok(())
+}
+
+// Thaws the NFT, revoking the vault delegation when the owner signed.
+#[allow(clippy::too_many_arguments)]
+fn thaw_nft<'info>(
+  token_program: &Program<'info, Token>,
+  token_metadata_program: &AccountInfo<'info>,
+  vault: &AccountInfo<'info>,
+  owner: &AccountInfo<'info>,
+  token_account: &AccountInfo<'info>,
+  edition: &AccountInfo<'info>,
+  mint: &AccountInfo<'info>,
+  seeds: &[&[u8]],
+  revoke: bool,
+) -> Result<()> {
+  invoke_signed(
+    &thaw_delegated_account(
+        token_metadata_program.key(),
+        vault.key(),
+        token_account.key(),
+        edition.key(),
+        mint.key(),
+    ),
+    &[
+        vault.clone(),
+        token_account.clone(),
+        edition.clone(),
+        mint.clone()
+    ],
+    &[seeds]
+  )?;
+
+  if revoke {
+    let cpi_context = CpiContext::new(
+        token_program.to_account_info(),
+        anchor_spl::token::Revoke {
+            source: token_account.clone(),
+            authority: owner.clone()
+        }
+    );
+
+    anchor_spl::token::revoke(cpi_context)?;
+  }
+
+  Ok(())
+}
+
+// Checks the NFT against the vault gates and returns its reward weight and unlock time.
+fn stake_terms(
+  vault: &Vault,
+  metadata: &Metadata,
+  weight: u64,
+  weight_proof: &[[u8; 32]],
+  allowlist_proof: &[[u8; 32]],
+  lock_tier: Option<u8>,
+  now: u64,
+) -> Result<(u64, u64)> {
+  // NFT must belong to the verified collection or be created by the verified whitelist creator.
+  require!(is_collection_nft(vault, metadata), CustomError::WrongNFT);
+
+  if vault.allowlist_root != [0; 32] {
+    let leaf = keccak::hashv(&[metadata.mint.as_ref()]).0;
+    require!(verify_proof(allowlist_proof, vault.allowlist_root, leaf), CustomError::NotAllowlisted);
+  }
+
+  let mut nft_weight = DEFAULT_NFT_WEIGHT;
+  if vault.weights_root != [0; 32] {
+    let leaf = keccak::hashv(&[metadata.mint.as_ref(), &weight.to_le_bytes()]).0;
+    require!(verify_proof(weight_proof, vault.weights_root, leaf), CustomError::InvalidProof);
+    nft_weight = weight;
+  }
+
+  // Unlocked NFTs earn at 1x, locked ones at their tier multiplier.
+  let mut unlock_time = 0;
+  let mut multiplier_bps = BPS_DENOMINATOR;
+  if let Some(tier_index) = lock_tier {
+    require!((tier_index as usize) < MAX_LOCK_TIERS, CustomError::InvalidLockTier);
+    let tier = vault.lock_tiers[tier_index as usize];
+    require!(tier.duration > 0, CustomError::InvalidLockTier);
+    unlock_time = now.checked_add(tier.duration).ok_or(CustomError::MathOverflow)?;
+    multiplier_bps = tier.multiplier_bps;
+  }
+  nft_weight = nft_weight.checked_mul(multiplier_bps).ok_or(CustomError::MathOverflow)?;
+
+  Ok((nft_weight, unlock_time))
+}
+
+// Takes the pass discount off the fee when the owner holds the vault's pass NFT.
+fn discounted_fee(vault: &Vault, fee: u64, owner: &Pubkey, pass_token_account: &AccountInfo) -> Result<u64> {
+  if fee == 0 || vault.pass_mint == Pubkey::default() {
+    return Ok(fee);
+  }
+
+  let holds_pass = match Account::<TokenAccount>::try_from(pass_token_account) {
+    Ok(token_account) => {
+      token_account.mint == vault.pass_mint && token_account.owner == *owner && token_account.amount > 0
+    }
+    Err(_) => false,
+  };
+  if !holds_pass {
+    return Ok(fee);
+  }
+
+  let discount = (fee as u128)
+    .checked_mul(vault.pass_discount_bps as u128).ok_or(CustomError::MathOverflow)?
+    .checked_div(BPS_DENOMINATOR as u128).ok_or(CustomError::MathOverflow)? as u64;
+  Ok(fee.checked_sub(discount).ok_or(CustomError::MathOverflow)?)
+}
+
+// Pays a fee into the fee treasury, in lamports or in `fee_mint` tokens.
+#[allow(clippy::too_many_arguments)]
+fn charge_fee<'info>(
+  fee_mint: &Pubkey,
+  payer: &AccountInfo<'info>,
+  fee_treasury: &AccountInfo<'info>,
+  payer_fee_account: &AccountInfo<'info>,
+  fee_treasury_fee_account: &AccountInfo<'info>,
+  token_program: &AccountInfo<'info>,
+  system_program: &AccountInfo<'info>,
+  amount: u64,
+) -> Result<()> {
+  if *fee_mint == Pubkey::default() {
+    invoke(
+      &anchor_lang::solana_program::system_instruction::transfer(
+        payer.key,
+        fee_treasury.key,
+        amount,
+      ),
+      &[
+        payer.clone(),
+        fee_treasury.clone(),
+        system_program.clone(),
+      ],
+    )?;
+  } else {
+    require_keys_eq!(
+      fee_treasury_fee_account.key(),
+      get_associated_token_address(fee_treasury.key, fee_mint),
+      CustomError::AccountMismatch
+    );
+
+    let cpi_context = CpiContext::new(
+      token_program.clone(),
+      anchor_spl::token::Transfer {
+        from: payer_fee_account.clone(),
+        to: fee_treasury_fee_account.clone(),
+        authority: payer.clone(),
+      }
+    );
+    anchor_spl::token::transfer(cpi_context, amount)?;
+  }
+
+  Ok(())
+}
+
+// Creates the receipt PDA of an NFT staked through `stake_many`, the way Anchor's `init` does,
+// so lamports sent to the address beforehand cannot block the stake.
+fn create_receipt<'info>(
+  payer: &AccountInfo<'info>,
+  receipt_account: &AccountInfo<'info>,
+  system_program: &AccountInfo<'info>,
+  rent: &Rent,
+  receipt: &StakeReceipt,
+  seeds: &[&[u8]],
+) -> Result<()> {
+  let space = StakeReceipt::LEN + 8;
+  let current_lamports = receipt_account.lamports();
+  if current_lamports == 0 {
+    anchor_lang::system_program::create_account(
+      CpiContext::new_with_signer(
+        system_program.clone(),
+        anchor_lang::system_program::CreateAccount {
+          from: payer.clone(),
+          to: receipt_account.clone(),
+        },
+        &[seeds]
+      ),
+      rent.minimum_balance(space),
+      space as u64,
+      &crate::ID,
+    )?;
+  } else {
+    let required_lamports = rent.minimum_balance(space).saturating_sub(current_lamports);
+    if required_lamports > 0 {
+      anchor_lang::system_program::transfer(
+        CpiContext::new(
+          system_program.clone(),
+          anchor_lang::system_program::Transfer {
+            from: payer.clone(),
+            to: receipt_account.clone(),
+          },
+        ),
+        required_lamports,
+      )?;
+    }
+    anchor_lang::system_program::allocate(
+      CpiContext::new_with_signer(
+        system_program.clone(),
+        anchor_lang::system_program::Allocate {
+          account_to_allocate: receipt_account.clone(),
+        },
+        &[seeds]
+      ),
+      space as u64,
+    )?;
+    anchor_lang::system_program::assign(
+      CpiContext::new_with_signer(
+        system_program.clone(),
+        anchor_lang::system_program::Assign {
+          account_to_assign: receipt_account.clone(),
+        },
+        &[seeds]
+      ),
+      &crate::ID,
+    )?;
+  }
+
+  let mut data = receipt_account.try_borrow_mut_data()?;
+  let mut writer: &mut [u8] = &mut data;
+  receipt.try_serialize(&mut writer)?;
+
+  Ok(())
+}
+
 /*
 * Stake:: Stake Instruction - Stake the user's NFT.
 */
-pub fn handle_stake(ctx: Context<Stake>) -> Result<()> {
+pub fn handle_stake(
+  ctx: Context<Stake>,
+  weight: u64,
+  weight_proof: Vec<[u8; 32]>,
+  allowlist_proof: Vec<[u8; 32]>,
+  lock_tier: Option<u8>,
+) -> Result<()> {
   let vault = &mut ctx.accounts.vault.load_mut()?;
   let user = &mut ctx.accounts.user;
-  let token_mint = ctx.accounts.token_mint.key();
+  require!(!vault.is_paused(PAUSE_STAKE) && !vault.in_emergency(), CustomError::StakingPaused);
 
   let mut is_max_staked = false;
-  if user.mint_staked_count >= 200 {
+  if user.mint_staked_count >= MAX_NFT_PER_USER {
     is_max_staked = true
   }
   require_eq!(is_max_staked, false, CustomError::MaxStaked);
 
   // Load the NFT metadata
-  let metadata = spl_token_metadata::state::Metadata::from_account_info(&ctx.accounts.nft_metadata_account)?;
-  let creators = metadata.data.creators.unwrap();
-  let mut creator_found = false;
-  for creator in creators {
-    if creator.address.key() == vault.creator_address {
-      creator_found = true;
-    }
-  }
+  let metadata = Metadata::from_account_info(&ctx.accounts.nft_metadata_account)?;
+  require_keys_eq!(metadata.mint, ctx.accounts.token_mint.key(), CustomError::MintMismatch);
 
-  // NFT must be created by whitelist owner.
-  require_eq!(creator_found, true, CustomError::WrongNFT);
+  let token_mint = ctx.accounts.token_mint.key();
+  let now = current_timestamp()?;
+  let (nft_weight, unlock_time) = stake_terms(
+    vault,
+    &metadata,
+    weight,
+    &weight_proof,
+    &allowlist_proof,
+    lock_tier,
+    now,
+  )?;
   
-  if vault.stake_fee > 0 {
-    invoke(
-      &anchor_lang::solana_program::system_instruction::transfer(
-        &ctx.accounts.staker.key,
-        &ctx.accounts.vault.key(),
-        vault.stake_fee,
-      ),
-      &[
-        ctx.accounts.staker.to_account_info().clone(),
-        ctx.accounts.vault.to_account_info().clone(),
-        ctx.accounts.system_program.to_account_info().clone(),
-      ],
+  let fee = discounted_fee(
+    vault,
+    vault.stake_fee,
+    &ctx.accounts.staker.key(),
+    &ctx.accounts.pass_token_account,
+  )?;
+  if fee > 0 {
+    charge_fee(
+      &vault.fee_mint,
+      &ctx.accounts.staker.to_account_info(),
+      &ctx.accounts.fee_treasury.to_account_info(),
+      &ctx.accounts.staker_fee_account,
+      &ctx.accounts.fee_treasury_fee_account,
+      &ctx.accounts.token_program.to_account_info(),
+      &ctx.accounts.system_program.to_account_info(),
+      fee,
     )?;
-    vault.total_earned = vault.total_earned.checked_add(vault.stake_fee).unwrap();
+    vault.total_fees_collected = vault.total_fees_collected.checked_add(fee).ok_or(CustomError::MathOverflow)?;
   }
   
-  vault.update_payout_round();
-  user.add_item(vault, token_mint)?;
+  vault.update_payout_round()?;
+  user.add_item(vault, nft_weight)?;
 
-  let cpi_context = CpiContext::new(
-    ctx.accounts.token_program.to_account_info(),
-    anchor_spl::token::Approve {
-        to: ctx.accounts.staker_ata.to_account_info().clone(),
-        delegate: ctx.accounts.vault.to_account_info(),
-        authority: ctx.accounts.staker.to_account_info()
-    }
-  );
+  emit!(Staked {
+    vault: ctx.accounts.vault.key(),
+    user: ctx.accounts.staker.key(),
+    mint: token_mint,
+    weight: nft_weight,
+    unlock_time,
+    timestamp: now as i64,
+  });
+
+  let receipt = &mut ctx.accounts.receipt;
+  receipt.vault = ctx.accounts.vault.key();
+  receipt.owner = ctx.accounts.staker.key();
+  receipt.mint = token_mint;
+  receipt.staked_at = now;
+  receipt.unlock_time = unlock_time;
+  receipt.weight = nft_weight;
+  receipt.stake_reward_per_share = vault.reward_per_share;
+  receipt.bump = *ctx.bumps.get("receipt").unwrap();
 
-  anchor_spl::token::approve(cpi_context, 1)?;
-  
-  // Get the NFT from the Vault,
   let token_vault_bump = vault.bump;
+  let pool_id = vault.pool_id;
   let seeds = &[
     b"vault".as_ref(),
+    pool_id.as_ref(),
     &[token_vault_bump],
   ];
 
-  invoke_signed(
-      &freeze_delegated_account(
-          ctx.accounts.token_metadata_program.key(),
-          ctx.accounts.vault.key(),
-          ctx.accounts.staker_ata.key(),
-          ctx.accounts.edition.key(),
-          ctx.accounts.token_mint.key(),
-      ),
-      &[
-          ctx.accounts.vault.to_account_info(),
-          ctx.accounts.staker_ata.to_account_info(),
-          ctx.accounts.edition.to_account_info(),
-          ctx.accounts.token_mint.to_account_info()
-      ],
-      &[seeds]
+  freeze_nft(
+    &ctx.accounts.token_program,
+    &ctx.accounts.token_metadata_program,
+    &ctx.accounts.vault.to_account_info(),
+    &ctx.accounts.staker.to_account_info(),
+    &ctx.accounts.staker_ata.to_account_info(),
+    &ctx.accounts.edition,
+    &ctx.accounts.token_mint.to_account_info(),
+    seeds,
   )?;
 
   Ok(())
@@ -96,80 +416,569 @@ pub fn handle_stake(ctx: Context<Stake>) -> Result<()> {
 pub fn handle_unstake(ctx: Context<Unstake>) -> Result<()> {
   let vault = &mut ctx.accounts.vault.load_mut()?;
   let user = &mut ctx.accounts.user;
-  let token_mint = ctx.accounts.token_mint.key();
   
   // Staker should own staker account
   require_keys_eq!(
     ctx.accounts.staker.key(),
-    user.key(),
+    user.key,
+    CustomError::KeyMismatch
+  );
+
+  // Staker should own the staked NFT
+  require_keys_eq!(
+    ctx.accounts.staker.key(),
+    ctx.accounts.receipt.owner,
     CustomError::KeyMismatch
   );
 
+  // Emergency mode releases NFTs regardless of lock-ups, fees and reward accrual.
+  let emergency = vault.in_emergency();
+  let now = current_timestamp()?;
+  require!(emergency || now >= ctx.accounts.receipt.unlock_time, CustomError::StillLocked);
+
   // If the staker key is not the same as the signer key,
-  // then the signer account should match authority key.
-  if ctx.accounts.staker.key() != ctx.accounts.signer.key() {
+  // then the signer account should match operator key.
+  let forced = ctx.accounts.staker.key() != ctx.accounts.signer.key();
+  if forced {
     require_keys_eq!(
       ctx.accounts.signer.key(),
-      vault.authority.key(),
+      vault.operator.key(),
       CustomError::Unauthorized
     );
   }
 
-  if vault.unstake_fee > 0 {
-    invoke(
-      &anchor_lang::solana_program::system_instruction::transfer(
-        &ctx.accounts.staker.key,
-        &ctx.accounts.vault.key(),
-        vault.unstake_fee,
-      ),
-      &[
-        ctx.accounts.staker.to_account_info().clone(),
-        ctx.accounts.vault.to_account_info().clone(),
-        ctx.accounts.system_program.to_account_info().clone(),
-      ],
+  let fee = discounted_fee(
+    vault,
+    vault.unstake_fee,
+    &ctx.accounts.staker.key(),
+    &ctx.accounts.pass_token_account,
+  )?;
+  // The staker does not sign an operator unstake, so they are never charged for it.
+  if fee > 0 && !emergency && !forced {
+    charge_fee(
+      &vault.fee_mint,
+      &ctx.accounts.staker.to_account_info(),
+      &ctx.accounts.fee_treasury.to_account_info(),
+      &ctx.accounts.staker_fee_account,
+      &ctx.accounts.fee_treasury_fee_account,
+      &ctx.accounts.token_program.to_account_info(),
+      &ctx.accounts.system_program.to_account_info(),
+      fee,
     )?;
-    vault.total_earned = vault.total_earned.checked_add(vault.unstake_fee).unwrap();
+    vault.total_fees_collected = vault.total_fees_collected.checked_add(fee).ok_or(CustomError::MathOverflow)?;
+  }
 
+  if !emergency {
+    vault.update_payout_round()?;
   }
-  
-  vault.update_payout_round();
-  user.remove_item(vault, token_mint)?;
+  user.remove_item(vault, ctx.accounts.receipt.weight)?;
+
+  emit!(Unstaked {
+    vault: ctx.accounts.vault.key(),
+    user: ctx.accounts.staker.key(),
+    mint: ctx.accounts.token_mint.key(),
+    weight: ctx.accounts.receipt.weight,
+    timestamp: now as i64,
+  });
 
-  // Get the NFT from the Vault,
   let token_vault_bump = vault.bump;
+  let pool_id = vault.pool_id;
+  let seeds = &[
+    b"vault".as_ref(),
+    pool_id.as_ref(),
+    &[token_vault_bump],
+  ];
+
+  // The NFT stays delegated to the vault when the operator force-unstakes it.
+  thaw_nft(
+    &ctx.accounts.token_program,
+    &ctx.accounts.token_metadata_program,
+    &ctx.accounts.vault.to_account_info(),
+    &ctx.accounts.staker.to_account_info(),
+    &ctx.accounts.staker_ata.to_account_info(),
+    &ctx.accounts.edition,
+    &ctx.accounts.token_mint.to_account_info(),
+    seeds,
+    ctx.accounts.staker.key() == ctx.accounts.signer.key(),
+  )?;
+
+  Ok(())
+}
+
+/*
+* EmergencyUnstake:: Emergency Unstake Instruction - Release the user's NFT without reward accounting, forfeiting pending rewards.
+*/
+pub fn handle_emergency_unstake(ctx: Context<EmergencyUnstake>) -> Result<()> {
+  // Staker should own the staked NFT
+  require_keys_eq!(
+    ctx.accounts.staker.key(),
+    ctx.accounts.receipt.owner,
+    CustomError::KeyMismatch
+  );
+
+  let weight = ctx.accounts.receipt.weight;
+  let (forfeited_rewards, token_vault_bump, pool_id) = {
+    let vault = &mut ctx.accounts.vault.load_mut()?;
+
+    // Lock-ups and the unstake fee still hold outside emergency mode,
+    // reading the clock never touches the payout math.
+    if !vault.in_emergency() {
+      let now = current_timestamp()?;
+      require!(now >= ctx.accounts.receipt.unlock_time, CustomError::StillLocked);
+
+      let fee = discounted_fee(
+        vault,
+        vault.unstake_fee,
+        &ctx.accounts.staker.key(),
+        &ctx.accounts.pass_token_account,
+      )?;
+      if fee > 0 {
+        charge_fee(
+          &vault.fee_mint,
+          &ctx.accounts.staker.to_account_info(),
+          &ctx.accounts.fee_treasury.to_account_info(),
+          &ctx.accounts.staker_fee_account,
+          &ctx.accounts.fee_treasury_fee_account,
+          &ctx.accounts.token_program.to_account_info(),
+          &ctx.accounts.system_program.to_account_info(),
+          fee,
+        )?;
+        vault.total_fees_collected = vault.total_fees_collected.checked_add(fee).ok_or(CustomError::MathOverflow)?;
+      }
+    }
+
+    let forfeited_rewards = ctx.accounts.user.forfeit_item(vault, weight);
+    (forfeited_rewards, vault.bump, vault.pool_id)
+  };
 
   let seeds = &[
     b"vault".as_ref(),
+    pool_id.as_ref(),
     &[token_vault_bump],
   ];
-  invoke_signed(
-    &thaw_delegated_account(
-        ctx.accounts.token_metadata_program.key(),
-        ctx.accounts.vault.key(),
-        ctx.accounts.staker_ata.key(),
-        ctx.accounts.edition.key(),
-        ctx.accounts.token_mint.key(),
-    ),
-    &[
-        ctx.accounts.vault.to_account_info(),
-        ctx.accounts.staker_ata.to_account_info(),
-        ctx.accounts.edition.to_account_info(),
-        ctx.accounts.token_mint.to_account_info()
-    ],
-    &[seeds]
+
+  thaw_nft(
+    &ctx.accounts.token_program,
+    &ctx.accounts.token_metadata_program,
+    &ctx.accounts.vault.to_account_info(),
+    &ctx.accounts.staker.to_account_info(),
+    &ctx.accounts.staker_ata.to_account_info(),
+    &ctx.accounts.edition,
+    &ctx.accounts.token_mint.to_account_info(),
+    seeds,
+    true,
   )?;
 
+  emit!(EmergencyUnstaked {
+    vault: ctx.accounts.vault.key(),
+    user: ctx.accounts.staker.key(),
+    mint: ctx.accounts.token_mint.key(),
+    weight,
+    forfeited_rewards,
+    timestamp: current_timestamp()? as i64,
+  });
 
-  if ctx.accounts.staker.key() == ctx.accounts.signer.key() {
-    let cpi_context = CpiContext::new(
-        ctx.accounts.token_program.to_account_info(),
-        anchor_spl::token::Revoke {
-            source: ctx.accounts.staker_ata.to_account_info(),
-            authority: ctx.accounts.staker.to_account_info()
-        }
+  Ok(())
+}
+
+/*
+* EmergencyUnstakeBooster:: Emergency Unstake Booster Instruction - Release the user's booster NFT without reward accounting, forfeiting pending rewards.
+*/
+pub fn handle_emergency_unstake_booster(ctx: Context<EmergencyUnstakeBooster>) -> Result<()> {
+  let booster_mint = ctx.accounts.booster_mint.key();
+  let (forfeited_rewards, token_vault_bump, pool_id) = {
+    let vault = &mut ctx.accounts.vault.load_mut()?;
+    let forfeited_rewards = ctx.accounts.user.forfeit_booster(vault, booster_mint)?;
+    (forfeited_rewards, vault.bump, vault.pool_id)
+  };
+
+  let seeds = &[
+    b"vault".as_ref(),
+    pool_id.as_ref(),
+    &[token_vault_bump],
+  ];
+
+  thaw_nft(
+    &ctx.accounts.token_program,
+    &ctx.accounts.token_metadata_program,
+    &ctx.accounts.vault.to_account_info(),
+    &ctx.accounts.staker.to_account_info(),
+    &ctx.accounts.staker_ata.to_account_info(),
+    &ctx.accounts.edition,
+    &ctx.accounts.booster_mint.to_account_info(),
+    seeds,
+    true,
+  )?;
+
+  emit!(BoosterEmergencyUnstaked {
+    vault: ctx.accounts.vault.key(),
+    user: ctx.accounts.staker.key(),
+    mint: booster_mint,
+    forfeited_rewards,
+    timestamp: current_timestamp()? as i64,
+  });
+
+  Ok(())
+}
+
+/*
+* StakeBooster:: Stake Booster Instruction - Stake a booster NFT multiplying the user's rewards.
+*/
+pub fn handle_stake_booster(ctx: Context<StakeBooster>) -> Result<()> {
+  let vault = &mut ctx.accounts.vault.load_mut()?;
+  let user = &mut ctx.accounts.user;
+  require!(!vault.is_paused(PAUSE_STAKE) && !vault.in_emergency(), CustomError::StakingPaused);
+
+  let metadata = Metadata::from_account_info(&ctx.accounts.nft_metadata_account)?;
+  let booster_mint = ctx.accounts.booster_mint.key();
+  require_keys_eq!(metadata.mint, booster_mint, CustomError::MintMismatch);
+
+  let multiplier_bps = find_booster(vault, &metadata).ok_or(CustomError::NotBooster)?;
+
+  vault.update_payout_round()?;
+  user.add_booster(vault, booster_mint, multiplier_bps)?;
+
+  emit!(BoosterStaked {
+    vault: ctx.accounts.vault.key(),
+    user: ctx.accounts.staker.key(),
+    mint: booster_mint,
+    multiplier_bps,
+    timestamp: current_timestamp()? as i64,
+  });
+
+  let token_vault_bump = vault.bump;
+  let pool_id = vault.pool_id;
+  let seeds = &[
+    b"vault".as_ref(),
+    pool_id.as_ref(),
+    &[token_vault_bump],
+  ];
+
+  freeze_nft(
+    &ctx.accounts.token_program,
+    &ctx.accounts.token_metadata_program,
+    &ctx.accounts.vault.to_account_info(),
+    &ctx.accounts.staker.to_account_info(),
+    &ctx.accounts.staker_ata.to_account_info(),
+    &ctx.accounts.edition,
+    &ctx.accounts.booster_mint.to_account_info(),
+    seeds,
+  )?;
+
+  Ok(())
+}
+
+/*
+* UnstakeBooster:: Unstake Booster Instruction - Unstake the user's booster NFT.
+*/
+pub fn handle_unstake_booster(ctx: Context<UnstakeBooster>) -> Result<()> {
+  let vault = &mut ctx.accounts.vault.load_mut()?;
+  let user = &mut ctx.accounts.user;
+
+  // If the staker key is not the same as the signer key,
+  // then the signer account should match operator key.
+  if ctx.accounts.staker.key() != ctx.accounts.signer.key() {
+    require_keys_eq!(
+      ctx.accounts.signer.key(),
+      vault.operator.key(),
+      CustomError::Unauthorized
     );
+  }
 
-    anchor_spl::token::revoke(cpi_context)?;
+  if !vault.in_emergency() {
+    vault.update_payout_round()?;
+  }
+  user.remove_booster(vault, ctx.accounts.booster_mint.key())?;
+
+  emit!(BoosterUnstaked {
+    vault: ctx.accounts.vault.key(),
+    user: ctx.accounts.staker.key(),
+    mint: ctx.accounts.booster_mint.key(),
+    timestamp: current_timestamp()? as i64,
+  });
+
+  let token_vault_bump = vault.bump;
+  let pool_id = vault.pool_id;
+  let seeds = &[
+    b"vault".as_ref(),
+    pool_id.as_ref(),
+    &[token_vault_bump],
+  ];
+
+  thaw_nft(
+    &ctx.accounts.token_program,
+    &ctx.accounts.token_metadata_program,
+    &ctx.accounts.vault.to_account_info(),
+    &ctx.accounts.staker.to_account_info(),
+    &ctx.accounts.staker_ata.to_account_info(),
+    &ctx.accounts.edition,
+    &ctx.accounts.booster_mint.to_account_info(),
+    seeds,
+    ctx.accounts.staker.key() == ctx.accounts.signer.key(),
+  )?;
+
+  Ok(())
+}
+
+/*
+* StakeMany:: Batch Stake Instruction - Stake several of the user's NFTs at once.
+*/
+pub fn handle_stake_many<'info>(
+  ctx: Context<'_, '_, '_, 'info, StakeMany<'info>>,
+  items: Vec<StakeItem>,
+) -> Result<()> {
+  let remaining_accounts = ctx.remaining_accounts;
+  require!(!items.is_empty(), CustomError::BatchAccountsMismatch);
+  require_eq!(
+    remaining_accounts.len(),
+    items.len().checked_mul(5).ok_or(CustomError::MathOverflow)?,
+    CustomError::BatchAccountsMismatch
+  );
+
+  let staker = ctx.accounts.staker.key();
+  let vault_key = ctx.accounts.vault.key();
+  let (fee, fee_mint, token_vault_bump, pool_id) = {
+    let vault = ctx.accounts.vault.load()?;
+    require!(!vault.is_paused(PAUSE_STAKE) && !vault.in_emergency(), CustomError::StakingPaused);
+    let fee_count = if vault.batch_fee_once == 1 { 1 } else { items.len() as u64 };
+    let fee = discounted_fee(
+      &vault,
+      vault.stake_fee.checked_mul(fee_count).ok_or(CustomError::MathOverflow)?,
+      &staker,
+      &ctx.accounts.pass_token_account,
+    )?;
+    (fee, vault.fee_mint, vault.bump, vault.pool_id)
+  };
+
+  if fee > 0 {
+    charge_fee(
+      &fee_mint,
+      &ctx.accounts.staker.to_account_info(),
+      &ctx.accounts.fee_treasury.to_account_info(),
+      &ctx.accounts.staker_fee_account,
+      &ctx.accounts.fee_treasury_fee_account,
+      &ctx.accounts.token_program.to_account_info(),
+      &ctx.accounts.system_program.to_account_info(),
+      fee,
+    )?;
+  }
+
+  let now = current_timestamp()?;
+  let mut receipts = Vec::with_capacity(items.len());
+  {
+    let vault = &mut ctx.accounts.vault.load_mut()?;
+    let user = &mut ctx.accounts.user;
+
+    let staked_count = user.mint_staked_count.checked_add(items.len() as u32).ok_or(CustomError::MathOverflow)?;
+    require!(staked_count <= MAX_NFT_PER_USER, CustomError::MaxStaked);
+
+    vault.total_fees_collected = vault.total_fees_collected.checked_add(fee).ok_or(CustomError::MathOverflow)?;
+    vault.update_payout_round()?;
+
+    for (item, accounts) in items.iter().zip(remaining_accounts.chunks(5)) {
+      let token_mint = Account::<Mint>::try_from(&accounts[0])?;
+      let staker_ata = Account::<TokenAccount>::try_from(&accounts[1])?;
+      require_keys_eq!(
+        staker_ata.key(),
+        get_associated_token_address(&staker, &token_mint.key()),
+        CustomError::AccountMismatch
+      );
+
+      let metadata = Metadata::from_account_info(&accounts[2])?;
+      require_keys_eq!(metadata.mint, token_mint.key(), CustomError::MintMismatch);
+
+      let (nft_weight, unlock_time) = stake_terms(
+        vault,
+        &metadata,
+        item.weight,
+        &item.weight_proof,
+        &item.allowlist_proof,
+        item.lock_tier,
+        now,
+      )?;
+      user.add_item(vault, nft_weight)?;
+
+      emit!(Staked {
+        vault: vault_key,
+        user: staker,
+        mint: token_mint.key(),
+        weight: nft_weight,
+        unlock_time,
+        timestamp: now as i64,
+      });
+
+      let (receipt_key, receipt_bump) = Pubkey::find_program_address(
+        &[b"receipt".as_ref(), vault_key.as_ref(), token_mint.key().as_ref()],
+        ctx.program_id,
+      );
+      require_keys_eq!(accounts[4].key(), receipt_key, CustomError::AccountMismatch);
+
+      receipts.push(StakeReceipt {
+        vault: vault_key,
+        owner: staker,
+        mint: token_mint.key(),
+        staked_at: now,
+        unlock_time,
+        weight: nft_weight,
+        stake_reward_per_share: vault.reward_per_share,
+        bump: receipt_bump,
+      });
+    }
+  }
+
+  let seeds = &[
+    b"vault".as_ref(),
+    pool_id.as_ref(),
+    &[token_vault_bump],
+  ];
+
+  for (receipt, accounts) in receipts.iter().zip(remaining_accounts.chunks(5)) {
+    let receipt_seeds = &[
+      b"receipt".as_ref(),
+      vault_key.as_ref(),
+      receipt.mint.as_ref(),
+      &[receipt.bump],
+    ];
+    create_receipt(
+      &ctx.accounts.staker.to_account_info(),
+      &accounts[4],
+      &ctx.accounts.system_program.to_account_info(),
+      &ctx.accounts.rent,
+      receipt,
+      receipt_seeds,
+    )?;
+
+    freeze_nft(
+      &ctx.accounts.token_program,
+      &ctx.accounts.token_metadata_program,
+      &ctx.accounts.vault.to_account_info(),
+      &ctx.accounts.staker.to_account_info(),
+      &accounts[1],
+      &accounts[3],
+      &accounts[0],
+      seeds,
+    )?;
+  }
+
+  Ok(())
+}
+
+/*
+* UnstakeMany:: Batch Unstake Instruction - Unstake several of the user's NFTs at once.
+*/
+pub fn handle_unstake_many<'info>(ctx: Context<'_, '_, '_, 'info, UnstakeMany<'info>>) -> Result<()> {
+  let remaining_accounts = ctx.remaining_accounts;
+  require_eq!(remaining_accounts.len() % 4, 0, CustomError::BatchAccountsMismatch);
+  let item_count = remaining_accounts.len() / 4;
+  require!(item_count > 0, CustomError::BatchAccountsMismatch);
+
+  let staker = ctx.accounts.staker.key();
+  let vault_key = ctx.accounts.vault.key();
+
+  // Staker should own staker account
+  require_keys_eq!(staker, ctx.accounts.user.key, CustomError::KeyMismatch);
+
+  let (operator, emergency, fee, fee_mint, token_vault_bump, pool_id) = {
+    let vault = ctx.accounts.vault.load()?;
+    let fee_count = if vault.batch_fee_once == 1 { 1 } else { item_count as u64 };
+    let mut fee = discounted_fee(
+      &vault,
+      vault.unstake_fee.checked_mul(fee_count).ok_or(CustomError::MathOverflow)?,
+      &staker,
+      &ctx.accounts.pass_token_account,
+    )?;
+    // Emergency mode releases NFTs regardless of lock-ups, fees and reward accrual.
+    if vault.in_emergency() {
+      fee = 0;
+    }
+    (vault.operator, vault.in_emergency(), fee, vault.fee_mint, vault.bump, vault.pool_id)
+  };
+
+  // If the staker key is not the same as the signer key,
+  // then the signer account should match operator key.
+  let forced = staker != ctx.accounts.signer.key();
+  if forced {
+    require_keys_eq!(ctx.accounts.signer.key(), operator, CustomError::Unauthorized);
+  }
+  let fee = if forced { 0 } else { fee };
+
+  if fee > 0 {
+    charge_fee(
+      &fee_mint,
+      &ctx.accounts.staker.to_account_info(),
+      &ctx.accounts.fee_treasury.to_account_info(),
+      &ctx.accounts.staker_fee_account,
+      &ctx.accounts.fee_treasury_fee_account,
+      &ctx.accounts.token_program.to_account_info(),
+      &ctx.accounts.system_program.to_account_info(),
+      fee,
+    )?;
+  }
+
+  let now = current_timestamp()?;
+  let mut receipts = Vec::with_capacity(item_count);
+  {
+    let vault = &mut ctx.accounts.vault.load_mut()?;
+    let user = &mut ctx.accounts.user;
+
+    vault.total_fees_collected = vault.total_fees_collected.checked_add(fee).ok_or(CustomError::MathOverflow)?;
+    if !emergency {
+      vault.update_payout_round()?;
+    }
+
+    for accounts in remaining_accounts.chunks(4) {
+      let token_mint = Account::<Mint>::try_from(&accounts[0])?;
+      let staker_ata = Account::<TokenAccount>::try_from(&accounts[1])?;
+      require_keys_eq!(
+        staker_ata.key(),
+        get_associated_token_address(&staker, &token_mint.key()),
+        CustomError::AccountMismatch
+      );
+
+      let receipt = Account::<StakeReceipt>::try_from(&accounts[3])?;
+      let (receipt_key, _) = Pubkey::find_program_address(
+        &[b"receipt".as_ref(), vault_key.as_ref(), token_mint.key().as_ref()],
+        ctx.program_id,
+      );
+      require_keys_eq!(receipt.key(), receipt_key, CustomError::AccountMismatch);
+
+      // Staker should own the staked NFT
+      require_keys_eq!(staker, receipt.owner, CustomError::KeyMismatch);
+      require!(emergency || now >= receipt.unlock_time, CustomError::StillLocked);
+
+      user.remove_item(vault, receipt.weight)?;
+
+      emit!(Unstaked {
+        vault: vault_key,
+        user: staker,
+        mint: token_mint.key(),
+        weight: receipt.weight,
+        timestamp: now as i64,
+      });
+      receipts.push(receipt);
+    }
+  }
+
+  let seeds = &[
+    b"vault".as_ref(),
+    pool_id.as_ref(),
+    &[token_vault_bump],
+  ];
+
+  for (receipt, accounts) in receipts.iter().zip(remaining_accounts.chunks(4)) {
+    thaw_nft(
+      &ctx.accounts.token_program,
+      &ctx.accounts.token_metadata_program,
+      &ctx.accounts.vault.to_account_info(),
+      &ctx.accounts.staker.to_account_info(),
+      &accounts[1],
+      &accounts[2],
+      &accounts[0],
+      seeds,
+      staker == ctx.accounts.signer.key(),
+    )?;
+
+    receipt.close(ctx.accounts.staker.to_account_info())?;
   }
 
   Ok(())
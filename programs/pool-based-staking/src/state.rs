@@ -3,78 +3,516 @@ use anchor_lang::solana_program::clock;
 
 use crate::constants::*;
 use crate::errors::*;
+use crate::events::*;
+
+pub fn current_timestamp() -> Result<u64> {
+    let clock = clock::Clock::get().map_err(|_| CustomError::ClockUnavailable)?;
+    Ok(clock.unix_timestamp.try_into().map_err(|_| CustomError::ClockUnavailable)?)
+}
 
 #[account(zero_copy)]
 pub struct Vault {
     pub authority: Pubkey,
+    // Proposed new authority, which takes over once it accepts. Default key when no transfer is pending.
+    pub pending_authority: Pubkey,
+    // Pauses staking and force-unstakes NFTs, appointed by the authority.
+    pub operator: Pubkey,
+    // Funds and drains rewards and withdraws fees, appointed by the authority.
+    pub treasurer: Pubkey,
+    // Seed of the vault PDA, typically the collection mint or creator address.
+    pub pool_id: Pubkey,
     pub creator_address: Pubkey,
-    pub total_earned: u64,
+    // Verified Metaplex collection allowed to stake, or the default key to gate on `creator_address` only.
+    pub collection_mint: Pubkey,
+    // Fees collected into the fee treasury, in lamports or in `fee_mint` units,
+    // including claim fees when rewards are paid in the same currency.
+    pub total_fees_collected: u64,
+    pub total_fees_withdrawn: u64,
     pub payout_schedule_started_time: u64,
     pub payout_round: u32,
     pub payout_interval: u64,
     pub payout_amount: u64,
+    // EMISSION_MODE_ROUNDS credits whole payout rounds, EMISSION_MODE_CONTINUOUS accrues every second.
+    pub emission_mode: u8,
+    // Timestamp rewards were last accrued up to in continuous mode.
+    pub last_accrual_time: u64,
+    // No rewards accrue after this timestamp, zero when emission never ends.
+    pub emission_end_time: u64,
+    // Rewards committed to stakers out of the funded amount, accrual stops once it is used up.
+    pub reward_budget: u64,
+    pub total_rewards_accrued: u64,
     pub total_staked_count: u32,
-    pub total_eligible_count: u32,
+    // Sum of the reward weights of every staked NFT, multiplied by each staker's booster.
+    pub total_staked_weight: u64,
+    // Reward funds currently held: funded minus claimed and drained.
     pub total_amount: u64,
+    pub total_funded: u64,
+    pub total_rewards_claimed: u64,
+    pub total_drained: u64,
     pub stake_fee: u64,
     pub unstake_fee: u64,
-    pub total_items_count: u32,
+    // 1 when `stake_many`/`unstake_many` charge the fee once per batch, 0 when they charge it per NFT.
+    pub batch_fee_once: u8,
+    // SPL token `stake_fee` and `unstake_fee` are charged in, or the default key to charge lamports.
+    pub fee_mint: Pubkey,
+    // NFT whose holders get `pass_discount_bps` off every fee, or the default key for no pass.
+    pub pass_mint: Pubkey,
+    pub pass_discount_bps: u64,
+    // Share of every claim sent to the fee treasury instead of the staker.
+    pub claim_fee_bps: u64,
+    // Claim fees in reward units, whichever currency rewards are paid in.
+    pub total_claim_fees: u64,
+    // SPL token paid out as rewards, or the default key when rewards are paid in SOL.
+    pub reward_mint: Pubkey,
+    // Merkle root of keccak(mint) leaves allowed to stake, or all zeros to allow the whole collection.
+    pub allowlist_root: [u8; 32],
+    // Merkle root of keccak(mint || weight) leaves, or all zeros when every NFT weighs DEFAULT_NFT_WEIGHT.
+    pub weights_root: [u8; 32],
+    pub lock_tiers: [LockTier; MAX_LOCK_TIERS],
+    pub boosters: [Booster; MAX_BOOSTERS],
+    // Seconds fee, payout and emission end changes and drains wait before they can be executed, zero to apply them instantly.
+    pub timelock_delay: u64,
+    // PAUSE_STAKE, PAUSE_CLAIM and PAUSE_FUND flags set by the operator.
+    pub paused: u8,
+    // 1 when unstaking skips fees, lock-ups and reward accrual, and staking and claiming are halted.
+    pub emergency_mode: u8,
+    pub pending_fees: PendingFees,
+    pub pending_payout: PendingPayout,
+    // A shorter timelock delay waits out the current one before it applies.
+    pub pending_timelock_delay: u64,
+    pub pending_timelock_delay_eta: u64,
+    pub pending_fee_config: PendingFeeConfig,
+    pub pending_claim_fee_bps: u64,
+    pub pending_claim_fee_eta: u64,
+    pub pending_batch_fee_once: u8,
+    pub pending_batch_fee_once_eta: u64,
+    pub pending_emission_end_time: u64,
+    pub pending_emission_end_eta: u64,
+    pub pending_drain: PendingDrain,
+    // Rewards accrued per unit of weight since the vault was created, scaled by REWARD_PRECISION.
+    pub reward_per_share: u128,
+    // Registered multisig signers, default keys for unused slots.
+    pub multisig_signers: [Pubkey; MAX_MULTISIG_SIGNERS],
+    // Approvals a proposal needs, zero when the multisig is disabled.
+    pub multisig_threshold: u8,
+    pub proposal_count: u64,
     pub bump: u8,
-    pub total_balance_items: [BalanceItem; TOTAL_COLLECTION_COUNT],
+    pub fee_treasury_bump: u8,
 }
 
 impl Vault {
     pub const LEN: usize = std::mem::size_of::<Vault>();
 
-    pub fn start_payout_schedule(&mut self) {
-        let now: u64 = clock::Clock::get().unwrap().unix_timestamp.try_into().unwrap();
+    // The vault PDA, for events emitted from methods that only have the account data.
+    pub fn address(&self) -> Result<Pubkey> {
+        Pubkey::create_program_address(&[b"vault".as_ref(), self.pool_id.as_ref(), &[self.bump]], &crate::ID)
+            .map_err(|_| error!(CustomError::AccountMismatch))
+    }
+
+    pub fn pays_token_rewards(&self) -> bool {
+        self.reward_mint != Pubkey::default()
+    }
+
+    pub fn is_paused(&self, flag: u8) -> bool {
+        self.paused & flag != 0
+    }
+
+    pub fn in_emergency(&self) -> bool {
+        self.emergency_mode == 1
+    }
+
+    // Drains, authority and role changes, fee, payout, emission end and timelock updates and
+    // cancelling queued changes go through proposals while enabled.
+    pub fn multisig_enabled(&self) -> bool {
+        self.multisig_threshold > 0
+    }
+
+    pub fn is_multisig_signer(&self, key: &Pubkey) -> bool {
+        *key != Pubkey::default() && self.multisig_signers.contains(key)
+    }
+
+    // A zero threshold with no signers disables the multisig.
+    pub fn set_multisig(&mut self, signers: &[Pubkey], threshold: u8) -> Result<()> {
+        require!(signers.len() <= MAX_MULTISIG_SIGNERS, CustomError::InvalidMultisig);
+        require!(threshold as usize <= signers.len(), CustomError::InvalidMultisig);
+        require!(threshold > 0 || signers.is_empty(), CustomError::InvalidMultisig);
+        for (i, signer) in signers.iter().enumerate() {
+            require!(
+                *signer != Pubkey::default() && !signers[..i].contains(signer),
+                CustomError::InvalidMultisig
+            );
+        }
+
+        self.multisig_signers = [Pubkey::default(); MAX_MULTISIG_SIGNERS];
+        self.multisig_signers[..signers.len()].copy_from_slice(signers);
+        self.multisig_threshold = threshold;
+
+        Ok(())
+    }
+
+    // Rewards that can still accrue before the budget runs out.
+    pub fn remaining_rewards(&self) -> Result<u64> {
+        Ok(self.reward_budget.checked_sub(self.total_rewards_accrued).ok_or(CustomError::MathOverflow)?)
+    }
+
+    pub fn record_fund(&mut self, amount: u64) -> Result<()> {
+        self.total_amount = self.total_amount.checked_add(amount).ok_or(CustomError::MathOverflow)?;
+        self.total_funded = self.total_funded.checked_add(amount).ok_or(CustomError::MathOverflow)?;
+        self.reward_budget = self.reward_budget.checked_add(amount).ok_or(CustomError::MathOverflow)?;
+
+        Ok(())
+    }
+
+    // Only the part of the budget that has not accrued to stakers yet can be drained.
+    pub fn record_drain(&mut self, amount: u64) -> Result<()> {
+        self.update_payout_round()?;
+        require!(amount <= self.remaining_rewards()?, CustomError::InsufficientRewardBudget);
+        self.reward_budget = self.reward_budget.checked_sub(amount).ok_or(CustomError::MathOverflow)?;
+        self.total_amount = self.total_amount.checked_sub(amount).ok_or(CustomError::MathOverflow)?;
+        self.total_drained = self.total_drained.checked_add(amount).ok_or(CustomError::MathOverflow)?;
+
+        Ok(())
+    }
+
+    // Splits a claimed amount into the claim fee and what the staker receives.
+    pub fn record_claim(&mut self, amount: u64) -> Result<(u64, u64)> {
+        self.total_amount = self.total_amount.checked_sub(amount).ok_or(CustomError::MathOverflow)?;
+        self.total_rewards_claimed = self.total_rewards_claimed.checked_add(amount).ok_or(CustomError::MathOverflow)?;
+
+        let fee = (amount as u128)
+            .checked_mul(self.claim_fee_bps as u128).ok_or(CustomError::MathOverflow)?
+            .checked_div(BPS_DENOMINATOR as u128).ok_or(CustomError::MathOverflow)? as u64;
+        self.total_claim_fees = self.total_claim_fees.checked_add(fee).ok_or(CustomError::MathOverflow)?;
+        // Claim fees paid in the fee currency share the treasury balance `withdraw_fees` draws from.
+        if self.reward_mint == self.fee_mint {
+            self.total_fees_collected = self.total_fees_collected.checked_add(fee).ok_or(CustomError::MathOverflow)?;
+        }
+
+        Ok((fee, amount.checked_sub(fee).ok_or(CustomError::MathOverflow)?))
+    }
+
+    // Lamports the vault account has to hold: rent exemption and any SOL reward not claimed yet.
+    pub fn required_lamports(&self, rent_exempt_minimum: u64) -> Result<u64> {
+        let mut required = rent_exempt_minimum;
+        if !self.pays_token_rewards() {
+            let unclaimed = self.reward_budget.checked_sub(self.total_rewards_claimed).ok_or(CustomError::MathOverflow)?;
+            required = required.checked_add(unclaimed).ok_or(CustomError::MathOverflow)?;
+        }
+        Ok(required)
+    }
+
+    // Restarting would drop everything accrued since the last settlement, so the schedule starts once.
+    pub fn start_payout_schedule(&mut self, now: u64) -> Result<()> {
+        require_eq!(self.payout_schedule_started_time, 0, CustomError::ScheduleAlreadyStarted);
         self.payout_schedule_started_time = now;
         self.payout_round = 1;
+        self.last_accrual_time = now;
+
+        Ok(())
     }
 
-    pub fn update_payout_round(&mut self) {
-        let now: u64 = clock::Clock::get().unwrap().unix_timestamp.try_into().unwrap();
-        if self.payout_schedule_started_time == 0 || self.total_eligible_count == 0 ||
-            now < self.payout_schedule_started_time.checked_add(
-                self.payout_interval.checked_mul(self.payout_round as u64).unwrap()
-            ).unwrap() {
-            return;
+    // Settles rewards owed under the current payout parameters before switching to the new ones.
+    pub fn set_payout_params(&mut self, payout_interval: u64, payout_amount: u64, now: u64) -> Result<()> {
+        require!(payout_interval > 0, CustomError::InvalidInterval);
+        self.update_payout_round_at(now)?;
+        if self.emission_mode == EMISSION_MODE_ROUNDS && self.payout_schedule_started_time > 0 {
+            // Count rounds from the start of the current one so elapsed rounds are not recounted at the new interval.
+            let elapsed_rounds = self.payout_round.checked_sub(1).ok_or(CustomError::MathOverflow)? as u64;
+            self.payout_schedule_started_time = self.payout_schedule_started_time.checked_add(
+                self.payout_interval.checked_mul(elapsed_rounds).ok_or(CustomError::MathOverflow)?
+            ).ok_or(CustomError::MathOverflow)?;
+            self.payout_round = 1;
         }
-        let prev_round = self.payout_round;
-        self.payout_round = now.checked_sub(self.payout_schedule_started_time).unwrap().checked_div(self.payout_interval).unwrap().checked_add(1).unwrap().try_into().unwrap();
-        let added_round = self.payout_round.checked_sub(prev_round).unwrap();
-        let earned = self.payout_amount.checked_div(self.total_eligible_count as u64).unwrap().checked_mul(added_round as u64).unwrap();
-        for i in 0..TOTAL_COLLECTION_COUNT {
-            let mut balance_item = self.total_balance_items[i];
-            if balance_item.state == 1 {
-                balance_item.state = 2;
-                balance_item.balance = balance_item.balance.checked_add(earned).unwrap();
-                self.total_eligible_count = self.total_eligible_count.checked_add(1).unwrap();
-                self.total_balance_items[i] = balance_item;
-            }
+        self.payout_interval = payout_interval;
+        self.payout_amount = payout_amount;
+
+        Ok(())
+    }
+
+    pub fn queue_fees(&mut self, stake_fee: u64, unstake_fee: u64, now: u64) -> Result<()> {
+        if self.timelock_delay == 0 {
+            self.stake_fee = stake_fee;
+            self.unstake_fee = unstake_fee;
+            return Ok(());
         }
+        self.pending_fees = PendingFees {
+            stake_fee,
+            unstake_fee,
+            eta: now.checked_add(self.timelock_delay).ok_or(CustomError::MathOverflow)?,
+        };
+
+        Ok(())
     }
 
-    fn add_balance_item(&mut self) -> usize {
-        for i in 0..TOTAL_COLLECTION_COUNT {
-            let mut balance_item = self.total_balance_items[i];
-            if balance_item.state == 0 {
-                balance_item.state = 1;
-                self.total_staked_count = self.total_staked_count.checked_add(1).unwrap();
-                if self.payout_schedule_started_time == 0 {
-                    balance_item.state = 2;
-                    self.total_eligible_count = self.total_eligible_count.checked_add(1).unwrap();
-                }
-                return i
+    pub fn queue_payout_params(&mut self, payout_interval: u64, payout_amount: u64, now: u64) -> Result<()> {
+        if self.timelock_delay == 0 {
+            return self.set_payout_params(payout_interval, payout_amount, now);
+        }
+        self.pending_payout = PendingPayout {
+            payout_interval,
+            payout_amount,
+            eta: now.checked_add(self.timelock_delay).ok_or(CustomError::MathOverflow)?,
+        };
+
+        Ok(())
+    }
+
+    pub fn queue_fee_config(&mut self, fee_mint: Pubkey, pass_mint: Pubkey, pass_discount_bps: u64, now: u64) -> Result<()> {
+        if self.timelock_delay == 0 {
+            self.fee_mint = fee_mint;
+            self.pass_mint = pass_mint;
+            self.pass_discount_bps = pass_discount_bps;
+            return Ok(());
+        }
+        self.pending_fee_config = PendingFeeConfig {
+            fee_mint,
+            pass_mint,
+            pass_discount_bps,
+            eta: now.checked_add(self.timelock_delay).ok_or(CustomError::MathOverflow)?,
+        };
+
+        Ok(())
+    }
+
+    pub fn queue_claim_fee(&mut self, claim_fee_bps: u64, now: u64) -> Result<()> {
+        if self.timelock_delay == 0 {
+            self.claim_fee_bps = claim_fee_bps;
+            return Ok(());
+        }
+        self.pending_claim_fee_bps = claim_fee_bps;
+        self.pending_claim_fee_eta = now.checked_add(self.timelock_delay).ok_or(CustomError::MathOverflow)?;
+
+        Ok(())
+    }
+
+    pub fn queue_batch_fee_mode(&mut self, batch_fee_once: u8, now: u64) -> Result<()> {
+        if self.timelock_delay == 0 {
+            self.batch_fee_once = batch_fee_once;
+            return Ok(());
+        }
+        self.pending_batch_fee_once = batch_fee_once;
+        self.pending_batch_fee_once_eta = now.checked_add(self.timelock_delay).ok_or(CustomError::MathOverflow)?;
+
+        Ok(())
+    }
+
+    pub fn queue_emission_end_time(&mut self, emission_end_time: u64, now: u64) -> Result<()> {
+        if self.timelock_delay == 0 {
+            return self.set_emission_end_time(emission_end_time, now);
+        }
+        self.pending_emission_end_time = emission_end_time;
+        self.pending_emission_end_eta = now.checked_add(self.timelock_delay).ok_or(CustomError::MathOverflow)?;
+
+        Ok(())
+    }
+
+    // Settles up to now first so the new end time is never applied retroactively. Once emissions
+    // have ended, settling stops at the old end, so the accrual clock is moved past the gap as well.
+    pub fn set_emission_end_time(&mut self, emission_end_time: u64, now: u64) -> Result<()> {
+        self.update_payout_round_at(now)?;
+        if self.emission_end_time > 0 && now > self.emission_end_time && self.payout_schedule_started_time > 0 {
+            self.last_accrual_time = now;
+            if self.emission_mode == EMISSION_MODE_ROUNDS {
+                // The round `now` falls in counts as paid, the next one pays at its usual boundary.
+                self.payout_round = now.checked_sub(self.payout_schedule_started_time).ok_or(CustomError::MathOverflow)?
+                    .checked_div(self.payout_interval).ok_or(CustomError::InvalidInterval)?
+                    .checked_add(1).ok_or(CustomError::MathOverflow)?
+                    .try_into().map_err(|_| CustomError::MathOverflow)?;
             }
         }
-        TOTAL_COLLECTION_COUNT
+        self.emission_end_time = emission_end_time;
+
+        Ok(())
     }
 
-    fn remove_balance_item(&mut self, index: usize) {
-        self.total_balance_items[index].state = 0;
-        self.total_eligible_count = self.total_eligible_count.checked_sub(1).unwrap();
-        self.total_staked_count = self.total_staked_count.checked_sub(1).unwrap();
+    // Drains move funds, so they cannot be applied by `execute_pending_changes`. The first request
+    // queues the drain, repeating it once the timelock has passed returns true and the caller sends the funds.
+    pub fn take_drain(&mut self, amount: u64, now: u64) -> Result<bool> {
+        if self.timelock_delay == 0 {
+            return Ok(true);
+        }
+        if self.pending_drain.eta != 0 && self.pending_drain.amount == amount {
+            require!(now >= self.pending_drain.eta, CustomError::DrainTimelocked);
+            self.pending_drain = PendingDrain::default();
+            return Ok(true);
+        }
+        self.pending_drain = PendingDrain {
+            amount,
+            eta: now.checked_add(self.timelock_delay).ok_or(CustomError::MathOverflow)?,
+        };
+
+        Ok(false)
+    }
+
+    // Longer delays apply right away, shorter ones only once the current delay has passed.
+    pub fn queue_timelock_delay(&mut self, timelock_delay: u64, now: u64) -> Result<()> {
+        if timelock_delay >= self.timelock_delay {
+            self.timelock_delay = timelock_delay;
+            self.pending_timelock_delay = 0;
+            self.pending_timelock_delay_eta = 0;
+            return Ok(());
+        }
+        self.pending_timelock_delay = timelock_delay;
+        self.pending_timelock_delay_eta = now.checked_add(self.timelock_delay).ok_or(CustomError::MathOverflow)?;
+
+        Ok(())
+    }
+
+    // Applies every queued change whose eta has passed, returning whether any was applied.
+    pub fn execute_pending_changes(&mut self, now: u64) -> Result<bool> {
+        let mut executed = false;
+        if self.pending_fees.eta != 0 && now >= self.pending_fees.eta {
+            self.stake_fee = self.pending_fees.stake_fee;
+            self.unstake_fee = self.pending_fees.unstake_fee;
+            self.pending_fees = PendingFees::default();
+            executed = true;
+        }
+        if self.pending_payout.eta != 0 && now >= self.pending_payout.eta {
+            let pending_payout = self.pending_payout;
+            self.set_payout_params(pending_payout.payout_interval, pending_payout.payout_amount, now)?;
+            self.pending_payout = PendingPayout::default();
+            executed = true;
+        }
+        if self.pending_timelock_delay_eta != 0 && now >= self.pending_timelock_delay_eta {
+            self.timelock_delay = self.pending_timelock_delay;
+            self.pending_timelock_delay = 0;
+            self.pending_timelock_delay_eta = 0;
+            executed = true;
+        }
+        if self.pending_fee_config.eta != 0 && now >= self.pending_fee_config.eta {
+            self.fee_mint = self.pending_fee_config.fee_mint;
+            self.pass_mint = self.pending_fee_config.pass_mint;
+            self.pass_discount_bps = self.pending_fee_config.pass_discount_bps;
+            self.pending_fee_config = PendingFeeConfig::default();
+            executed = true;
+        }
+        if self.pending_claim_fee_eta != 0 && now >= self.pending_claim_fee_eta {
+            self.claim_fee_bps = self.pending_claim_fee_bps;
+            self.pending_claim_fee_bps = 0;
+            self.pending_claim_fee_eta = 0;
+            executed = true;
+        }
+        if self.pending_batch_fee_once_eta != 0 && now >= self.pending_batch_fee_once_eta {
+            self.batch_fee_once = self.pending_batch_fee_once;
+            self.pending_batch_fee_once = 0;
+            self.pending_batch_fee_once_eta = 0;
+            executed = true;
+        }
+        if self.pending_emission_end_eta != 0 && now >= self.pending_emission_end_eta {
+            self.set_emission_end_time(self.pending_emission_end_time, now)?;
+            self.pending_emission_end_time = 0;
+            self.pending_emission_end_eta = 0;
+            executed = true;
+        }
+        Ok(executed)
+    }
+
+    pub fn cancel_pending_changes(&mut self) {
+        self.pending_fees = PendingFees::default();
+        self.pending_payout = PendingPayout::default();
+        self.pending_timelock_delay = 0;
+        self.pending_timelock_delay_eta = 0;
+        self.pending_fee_config = PendingFeeConfig::default();
+        self.pending_claim_fee_bps = 0;
+        self.pending_claim_fee_eta = 0;
+        self.pending_batch_fee_once = 0;
+        self.pending_batch_fee_once_eta = 0;
+        self.pending_emission_end_time = 0;
+        self.pending_emission_end_eta = 0;
+        self.pending_drain = PendingDrain::default();
+    }
+
+    pub fn update_payout_round(&mut self) -> Result<()> {
+        self.update_payout_round_at(current_timestamp()?)
+    }
+
+    pub fn update_payout_round_at(&mut self, now: u64) -> Result<()> {
+        let now = self.clamp_to_emission_end(now);
+        let added_round = self.accrue_rewards(now)?;
+        if added_round == 0 {
+            return Ok(());
+        }
+
+        emit!(PayoutRoundAdvanced {
+            vault: self.address()?,
+            payout_round: self.payout_round,
+            added_rounds: added_round,
+            reward_per_share: self.reward_per_share,
+            timestamp: now as i64,
+        });
+
+        Ok(())
+    }
+
+    // The current time, held at the emission end once emissions have stopped.
+    pub fn accrual_time(&self) -> Result<u64> {
+        Ok(self.clamp_to_emission_end(current_timestamp()?))
+    }
+
+    fn clamp_to_emission_end(&self, now: u64) -> u64 {
+        if self.emission_end_time > 0 && now > self.emission_end_time {
+            return self.emission_end_time;
+        }
+        now
+    }
+
+    // Accrues rewards up to `now` without emitting events, returning how many payout rounds were added.
+    pub fn accrue_rewards(&mut self, now: u64) -> Result<u32> {
+        if self.payout_schedule_started_time == 0 {
+            return Ok(0);
+        }
+        require!(self.payout_interval > 0, CustomError::InvalidInterval);
+        if self.emission_mode == EMISSION_MODE_CONTINUOUS {
+            self.accrue_continuous(now)?;
+            return Ok(0);
+        }
+        let next_round_time = self.payout_schedule_started_time.checked_add(
+            self.payout_interval.checked_mul(self.payout_round as u64).ok_or(CustomError::MathOverflow)?
+        ).ok_or(CustomError::MathOverflow)?;
+        if now < next_round_time {
+            return Ok(0);
+        }
+        let prev_round = self.payout_round;
+        self.payout_round = now.checked_sub(self.payout_schedule_started_time).ok_or(CustomError::MathOverflow)?
+            .checked_div(self.payout_interval).ok_or(CustomError::InvalidInterval)?
+            .checked_add(1).ok_or(CustomError::MathOverflow)?
+            .try_into().map_err(|_| CustomError::MathOverflow)?;
+        let added_round = self.payout_round.checked_sub(prev_round).ok_or(CustomError::MathOverflow)?;
+
+        self.distribute_rewards(self.payout_amount.checked_mul(added_round as u64).ok_or(CustomError::MathOverflow)?)?;
+
+        Ok(added_round)
+    }
+
+    // Spreads `payout_amount` per `payout_interval` evenly over every elapsed second.
+    fn accrue_continuous(&mut self, now: u64) -> Result<()> {
+        if now <= self.last_accrual_time {
+            return Ok(());
+        }
+        let elapsed = now.checked_sub(self.last_accrual_time).ok_or(CustomError::MathOverflow)?;
+        self.last_accrual_time = now;
+
+        let reward: u64 = (self.payout_amount as u128)
+            .checked_mul(elapsed as u128).ok_or(CustomError::MathOverflow)?
+            .checked_div(self.payout_interval as u128).ok_or(CustomError::InvalidInterval)?
+            .try_into().map_err(|_| CustomError::MathOverflow)?;
+        self.distribute_rewards(reward)
+    }
+
+    // Splits `reward` across the staked weight, capped by what is left of the budget.
+    fn distribute_rewards(&mut self, reward: u64) -> Result<()> {
+        // Periods that pass with nothing staked are not paid out to later stakers.
+        if self.total_staked_weight == 0 {
+            return Ok(());
+        }
+        let reward = reward.min(self.remaining_rewards()?);
+        self.total_rewards_accrued = self.total_rewards_accrued.checked_add(reward).ok_or(CustomError::MathOverflow)?;
+        let earned = (reward as u128)
+            .checked_mul(REWARD_PRECISION).ok_or(CustomError::MathOverflow)?
+            .checked_div(self.total_staked_weight as u128).ok_or(CustomError::MathOverflow)?;
+        self.reward_per_share = self.reward_per_share.checked_add(earned).ok_or(CustomError::MathOverflow)?;
+
+        Ok(())
     }
 }
 
@@ -82,94 +520,618 @@ impl Default for Vault {
     fn default() -> Vault {
         Vault {
             authority: Pubkey::default(),
+            pending_authority: Pubkey::default(),
+            operator: Pubkey::default(),
+            treasurer: Pubkey::default(),
+            pool_id: Pubkey::default(),
             creator_address: Pubkey::default(),
-            total_earned: 0,
+            collection_mint: Pubkey::default(),
+            total_fees_collected: 0,
+            total_fees_withdrawn: 0,
             payout_schedule_started_time: 0,
             payout_round: 0,
             payout_interval: 0,
             payout_amount: 0,
+            emission_mode: EMISSION_MODE_ROUNDS,
+            last_accrual_time: 0,
+            emission_end_time: 0,
+            reward_budget: 0,
+            total_rewards_accrued: 0,
             total_staked_count: 0,
-            total_eligible_count: 0,
+            total_staked_weight: 0,
             total_amount: 0,
+            total_funded: 0,
+            total_rewards_claimed: 0,
+            total_drained: 0,
             stake_fee: 0,
             unstake_fee: 0,
-            total_items_count: 0,
+            batch_fee_once: 0,
+            fee_mint: Pubkey::default(),
+            pass_mint: Pubkey::default(),
+            pass_discount_bps: 0,
+            claim_fee_bps: 0,
+            total_claim_fees: 0,
+            reward_mint: Pubkey::default(),
+            allowlist_root: [0; 32],
+            weights_root: [0; 32],
+            lock_tiers: [LockTier::default(); MAX_LOCK_TIERS],
+            boosters: [Booster::default(); MAX_BOOSTERS],
+            timelock_delay: 0,
+            paused: 0,
+            emergency_mode: 0,
+            pending_fees: PendingFees::default(),
+            pending_payout: PendingPayout::default(),
+            pending_timelock_delay: 0,
+            pending_timelock_delay_eta: 0,
+            pending_fee_config: PendingFeeConfig::default(),
+            pending_claim_fee_bps: 0,
+            pending_claim_fee_eta: 0,
+            pending_batch_fee_once: 0,
+            pending_batch_fee_once_eta: 0,
+            pending_emission_end_time: 0,
+            pending_emission_end_eta: 0,
+            pending_drain: PendingDrain::default(),
+            reward_per_share: 0,
+            multisig_signers: [Pubkey::default(); MAX_MULTISIG_SIGNERS],
+            multisig_threshold: 0,
+            proposal_count: 0,
             bump: 0,
-            total_balance_items: [BalanceItem::default(); TOTAL_COLLECTION_COUNT],
+            fee_treasury_bump: 0,
         }
     }
 }
 
 #[zero_copy]
-#[derive(Debug, PartialEq)]
-pub struct BalanceItem {
-    pub balance: u64,
-    pub state: u8,
+#[derive(Debug, Default, PartialEq)]
+pub struct LockTier {
+    // Lock-up in seconds, zero when the tier is disabled.
+    pub duration: u64,
+    // Reward multiplier applied to NFTs staked in this tier, BPS_DENOMINATOR meaning 1x.
+    pub multiplier_bps: u64,
 }
 
-impl Default for BalanceItem {
-    fn default() -> BalanceItem {
-        BalanceItem {
-            balance: 0,
-            state: 0,
-        }
-    }
+#[zero_copy]
+#[derive(Debug, Default, PartialEq)]
+pub struct PendingFees {
+    pub stake_fee: u64,
+    pub unstake_fee: u64,
+    // Timestamp the fees can be executed from, zero when nothing is queued.
+    pub eta: u64,
+}
+
+#[zero_copy]
+#[derive(Debug, Default, PartialEq)]
+pub struct PendingPayout {
+    pub payout_interval: u64,
+    pub payout_amount: u64,
+    // Timestamp the payout parameters can be executed from, zero when nothing is queued.
+    pub eta: u64,
 }
 
+#[zero_copy]
+#[derive(Debug, Default, PartialEq)]
+pub struct PendingDrain {
+    pub amount: u64,
+    // Timestamp the drain can be sent from, zero when nothing is queued.
+    pub eta: u64,
+}
+
+#[zero_copy]
+#[derive(Debug, Default, PartialEq)]
+pub struct PendingFeeConfig {
+    pub fee_mint: Pubkey,
+    pub pass_mint: Pubkey,
+    pub pass_discount_bps: u64,
+    // Timestamp the fee config can be executed from, zero when nothing is queued.
+    pub eta: u64,
+}
+
+#[zero_copy]
+#[derive(Debug, Default, PartialEq)]
+pub struct Booster {
+    // Booster mint or verified collection, the default key when the slot is unused.
+    pub key: Pubkey,
+    // Reward multiplier applied to the staker's whole weight, BPS_DENOMINATOR meaning 1x.
+    pub multiplier_bps: u64,
+}
 
 #[account]
 pub struct User {
     pub key: Pubkey,
-    pub eligible_count: u32,
+    pub vault: Pubkey,
     pub mint_staked_count: u32,
+    pub staked_weight: u64,
+    // Staked booster NFT, or the default key when none is staked.
+    pub booster_mint: Pubkey,
+    pub booster_multiplier_bps: u64,
+    // Share of `Vault::reward_per_share` already credited to this user, scaled by REWARD_PRECISION.
+    pub reward_debt: u128,
+    pub pending_rewards: u64,
     pub bump: u8,
-    pub staked_items: Vec<StakedNft>, 
 }
 
 impl User {
-    pub const LEN: usize = std::mem::size_of::<User>() + StakedNft::LEN * MAX_NFT_PER_USER;
+    pub const LEN: usize = std::mem::size_of::<User>();
 
-    pub fn add_item(&mut self, vault: &mut Vault, mint: Pubkey) -> Result<()> {
-        require!(self.staked_items.iter().any(|x| x.mint == mint) == false, CustomError::AlreadyStaked);
-        
-        let index = vault.add_balance_item();
-        if vault.total_balance_items[index].state == 2 {
-            self.eligible_count = self.eligible_count.checked_add(1).unwrap();
-        }
-        self.mint_staked_count = self.mint_staked_count.checked_add(1).unwrap();
+    // Weight this user contributes to `Vault::total_staked_weight`.
+    fn effective_weight(&self) -> Result<u64> {
+        Ok((self.staked_weight as u128)
+            .checked_mul(self.booster_multiplier_bps as u128).ok_or(CustomError::MathOverflow)?
+            .checked_div(BPS_DENOMINATOR as u128).ok_or(CustomError::MathOverflow)?
+            .try_into().map_err(|_| CustomError::MathOverflow)?)
+    }
+
+    fn accrued_rewards(&self, vault: &Vault) -> Result<u128> {
+        Ok((self.effective_weight()? as u128)
+            .checked_mul(vault.reward_per_share).ok_or(CustomError::MathOverflow)?
+            .checked_div(REWARD_PRECISION).ok_or(CustomError::MathOverflow)?)
+    }
+
+    // Moves everything earned since the last touch into `pending_rewards`.
+    fn settle(&mut self, vault: &Vault) -> Result<()> {
+        let accrued = self.accrued_rewards(vault)?;
+        let earned: u64 = accrued.checked_sub(self.reward_debt).ok_or(CustomError::MathOverflow)?
+            .try_into().map_err(|_| CustomError::MathOverflow)?;
+        self.pending_rewards = self.pending_rewards.checked_add(earned).ok_or(CustomError::MathOverflow)?;
+        self.reward_debt = accrued;
 
         Ok(())
     }
 
-    pub fn remove_item(&mut self, vault: &mut Vault, mint: Pubkey) -> Result<()> {
-        let index = self.staked_items.iter().position(|x| x.mint == mint).unwrap();
-        if vault.total_balance_items[index].state == 2 {
-            self.eligible_count = self.eligible_count.checked_sub(1).unwrap();
-        }
-        self.mint_staked_count = self.mint_staked_count.checked_sub(1).unwrap();
-        vault.remove_balance_item(index);
+    // Settles rewards under the current weight, then replaces this user's share of the vault weight.
+    fn reweight(&mut self, vault: &mut Vault, staked_weight: u64, booster_multiplier_bps: u64) -> Result<()> {
+        self.settle(vault)?;
+        vault.total_staked_weight = vault.total_staked_weight.checked_sub(self.effective_weight()?)
+            .ok_or(CustomError::MathOverflow)?;
+        self.staked_weight = staked_weight;
+        self.booster_multiplier_bps = booster_multiplier_bps;
+        vault.total_staked_weight = vault.total_staked_weight.checked_add(self.effective_weight()?)
+            .ok_or(CustomError::VaultFull)?;
+        self.reward_debt = self.accrued_rewards(vault)?;
 
         Ok(())
     }
 
-    pub fn claim(&mut self, vault: &mut Vault) -> u64 {
-        let mut total_pending_balance = 0u64;
-        for i in 0..self.mint_staked_count {
-            let index = self.staked_items[i as usize].index;
-            total_pending_balance = total_pending_balance.checked_add(vault.total_balance_items[index].balance).unwrap();
-            vault.total_balance_items[index].balance = 0;
-        }
+    pub fn add_item(&mut self, vault: &mut Vault, weight: u64) -> Result<()> {
+        let staked_weight = self.staked_weight.checked_add(weight).ok_or(CustomError::MathOverflow)?;
+        self.reweight(vault, staked_weight, self.booster_multiplier_bps)?;
+        self.mint_staked_count = self.mint_staked_count.checked_add(1).ok_or(CustomError::MaxStaked)?;
+        vault.total_staked_count = vault.total_staked_count.checked_add(1).ok_or(CustomError::VaultFull)?;
+
+        Ok(())
+    }
+
+    pub fn remove_item(&mut self, vault: &mut Vault, weight: u64) -> Result<()> {
+        let staked_weight = self.staked_weight.checked_sub(weight).ok_or(CustomError::NotStaked)?;
+        self.reweight(vault, staked_weight, self.booster_multiplier_bps)?;
+        self.mint_staked_count = self.mint_staked_count.checked_sub(1).ok_or(CustomError::NotStaked)?;
+        vault.total_staked_count = vault.total_staked_count.checked_sub(1).ok_or(CustomError::NotStaked)?;
+
+        Ok(())
+    }
+
+    // The forfeit paths below saturate instead of failing so NFTs can always be released.
+    fn saturating_effective_weight(&self) -> u64 {
+        ((self.staked_weight as u128).saturating_mul(self.booster_multiplier_bps as u128) / BPS_DENOMINATOR as u128)
+            .min(u64::MAX as u128) as u64
+    }
+
+    // Drops everything earned so far, settled or not, and returns it to the vault's reward budget.
+    fn forfeit_rewards(&mut self, vault: &mut Vault) -> u64 {
+        let accrued = (self.saturating_effective_weight() as u128).saturating_mul(vault.reward_per_share) / REWARD_PRECISION;
+        let unsettled = accrued.saturating_sub(self.reward_debt).min(u64::MAX as u128) as u64;
+        let forfeited = self.pending_rewards.saturating_add(unsettled);
+        self.pending_rewards = 0;
+        vault.total_rewards_accrued = vault.total_rewards_accrued.saturating_sub(forfeited);
+
+        forfeited
+    }
+
+    // Replaces this user's share of the vault weight once its rewards have been forfeited.
+    fn forfeit_reweight(&mut self, vault: &mut Vault, staked_weight: u64, booster_multiplier_bps: u64) {
+        let old_weight = self.saturating_effective_weight();
+        self.staked_weight = staked_weight;
+        self.booster_multiplier_bps = booster_multiplier_bps;
+        let new_weight = self.saturating_effective_weight();
+
+        vault.total_staked_weight = vault.total_staked_weight.saturating_sub(old_weight).saturating_add(new_weight);
+        self.reward_debt = (new_weight as u128).saturating_mul(vault.reward_per_share) / REWARD_PRECISION;
+    }
+
+    // Drops an NFT without running the payout math. Returns the forfeited rewards.
+    pub fn forfeit_item(&mut self, vault: &mut Vault, weight: u64) -> u64 {
+        let forfeited = self.forfeit_rewards(vault);
+        self.forfeit_reweight(vault, self.staked_weight.saturating_sub(weight), self.booster_multiplier_bps);
+        vault.total_staked_count = vault.total_staked_count.saturating_sub(1);
+        self.mint_staked_count = self.mint_staked_count.saturating_sub(1);
+
+        forfeited
+    }
+
+    // Drops the booster without running the payout math. Returns the forfeited rewards.
+    pub fn forfeit_booster(&mut self, vault: &mut Vault, mint: Pubkey) -> Result<u64> {
+        require_keys_eq!(self.booster_mint, mint, CustomError::BoosterNotStaked);
+        let forfeited = self.forfeit_rewards(vault);
+        self.forfeit_reweight(vault, self.staked_weight, BPS_DENOMINATOR);
+        self.booster_mint = Pubkey::default();
 
-        total_pending_balance
+        Ok(forfeited)
+    }
+
+    pub fn add_booster(&mut self, vault: &mut Vault, mint: Pubkey, multiplier_bps: u64) -> Result<()> {
+        require_keys_eq!(self.booster_mint, Pubkey::default(), CustomError::BoosterAlreadyStaked);
+        self.reweight(vault, self.staked_weight, multiplier_bps)?;
+        self.booster_mint = mint;
+
+        Ok(())
+    }
+
+    pub fn remove_booster(&mut self, vault: &mut Vault, mint: Pubkey) -> Result<()> {
+        require_keys_eq!(self.booster_mint, mint, CustomError::BoosterNotStaked);
+        self.reweight(vault, self.staked_weight, BPS_DENOMINATOR)?;
+        self.booster_mint = Pubkey::default();
+
+        Ok(())
+    }
+
+    pub fn claim(&mut self, vault: &mut Vault) -> Result<u64> {
+        self.settle(vault)?;
+        let total_pending_balance = self.pending_rewards;
+        self.pending_rewards = 0;
+
+        Ok(total_pending_balance)
     }
 }
 
-#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
-pub struct StakedNft {
-    pub index: usize,
+#[account]
+pub struct StakeReceipt {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
     pub mint: Pubkey,
+    pub staked_at: u64,
+    // Zero when the NFT was staked without a lock-up.
+    pub unlock_time: u64,
+    // Reward weight of the NFT including its lock tier multiplier.
+    pub weight: u64,
+    // `Vault::reward_per_share` when the NFT was staked, never updated afterwards since rewards are
+    // tracked per user through `User::reward_debt`.
+    pub stake_reward_per_share: u128,
+    pub bump: u8,
+}
+
+impl StakeReceipt {
+    pub const LEN: usize = std::mem::size_of::<StakeReceipt>();
+}
+
+#[account]
+pub struct Proposal {
+    pub vault: Pubkey,
+    pub index: u64,
+    pub proposer: Pubkey,
+    pub action: ProposalAction,
+    // Signers that approved the proposal, default keys for unused slots.
+    pub approvers: [Pubkey; MAX_MULTISIG_SIGNERS],
+    pub created_at: u64,
+    pub executed: bool,
+    pub bump: u8,
+}
+
+impl Proposal {
+    pub const LEN: usize = std::mem::size_of::<Proposal>();
+
+    // Only approvals from keys that are still registered signers count.
+    pub fn approval_count(&self, vault: &Vault) -> u8 {
+        self.approvers.iter()
+            .filter(|approver| vault.is_multisig_signer(approver))
+            .count() as u8
+    }
+
+    pub fn approve(&mut self, approver: Pubkey) -> Result<()> {
+        require!(!self.approvers.contains(&approver), CustomError::AlreadyApproved);
+        let slot = self.approvers.iter()
+            .position(|approver| *approver == Pubkey::default())
+            .ok_or(CustomError::AlreadyApproved)?;
+        self.approvers[slot] = approver;
+
+        Ok(())
+    }
 }
 
-impl StakedNft {
-    pub const LEN: usize = std::mem::size_of::<StakedNft>();
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ProposalAction {
+    // `recipient` is a wallet when rewards are paid in SOL, a reward token account otherwise.
+    Drain { amount: u64, recipient: Pubkey },
+    // The new authority still has to accept the transfer.
+    SetAuthority { new_authority: Pubkey },
+    SetFees { stake_fee: u64, unstake_fee: u64 },
+    SetPayoutParams { payout_interval: u64, payout_amount: u64 },
+    SetMultisig { signers: [Pubkey; MAX_MULTISIG_SIGNERS], threshold: u8 },
+    SetRoles { operator: Pubkey, treasurer: Pubkey },
+    SetFeeConfig { fee_mint: Pubkey, pass_mint: Pubkey, pass_discount_bps: u64 },
+    SetClaimFee { claim_fee_bps: u64 },
+    SetBatchFeeMode { charge_once: bool },
+    SetEmissionEndTime { emission_end_time: u64 },
+    SetTimelockDelay { timelock_delay: u64 },
+    CancelPendingChanges,
+}
+
+// The parameters a `VaultUpdated` event reports, as requested by the authority or an executed proposal.
+// Timelocked changes only take effect once `PendingChangesExecuted` is emitted.
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum VaultChange {
+    Fees { stake_fee: u64, unstake_fee: u64 },
+    PayoutParams { payout_interval: u64, payout_amount: u64 },
+    TimelockDelay { timelock_delay: u64 },
+    PendingChangesCancelled,
+    Creator { creator_address: Pubkey, collection_mint: Pubkey },
+    RewardMint { reward_mint: Pubkey },
+    WeightsRoot { weights_root: [u8; 32] },
+    AllowlistRoot { allowlist_root: [u8; 32] },
+    LockTier { index: u8, duration: u64, multiplier_bps: u64 },
+    Booster { index: u8, key: Pubkey, multiplier_bps: u64 },
+    BatchFeeMode { charge_once: bool },
+    FeeConfig { fee_mint: Pubkey, pass_mint: Pubkey, pass_discount_bps: u64 },
+    ClaimFee { claim_fee_bps: u64 },
+    EmissionMode { emission_mode: u8 },
+    EmissionEndTime { emission_end_time: u64 },
+}
+
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct StakeItem {
+    pub weight: u64,
+    pub weight_proof: Vec<[u8; 32]>,
+    pub allowlist_proof: Vec<[u8; 32]>,
+    pub lock_tier: Option<u8>,
+}
+
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct RewardRunway {
+    pub remaining_rewards: u64,
+    // When accrual stops at the current rate, either from the budget running out or the emission end.
+    pub runway_end_time: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const START: u64 = 1_000;
+    const INTERVAL: u64 = 100;
+    const PAYOUT: u64 = 1_000;
+
+    fn test_vault(emission_mode: u8, reward_budget: u64) -> Vault {
+        let pool_id = Pubkey::new_unique();
+        let (_, bump) = Pubkey::find_program_address(&[b"vault".as_ref(), pool_id.as_ref()], &crate::ID);
+        let mut vault = Vault {
+            pool_id,
+            bump,
+            emission_mode,
+            payout_interval: INTERVAL,
+            payout_amount: PAYOUT,
+            ..Vault::default()
+        };
+        vault.record_fund(reward_budget).unwrap();
+        vault.start_payout_schedule(START).unwrap();
+        vault
+    }
+
+    fn test_user() -> User {
+        User {
+            key: Pubkey::new_unique(),
+            vault: Pubkey::default(),
+            mint_staked_count: 0,
+            staked_weight: 0,
+            booster_mint: Pubkey::default(),
+            booster_multiplier_bps: BPS_DENOMINATOR,
+            reward_debt: 0,
+            pending_rewards: 0,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn rounds_split_by_weight() {
+        let mut vault = test_vault(EMISSION_MODE_ROUNDS, 100_000);
+        let mut alice = test_user();
+        let mut bob = test_user();
+        alice.add_item(&mut vault, BPS_DENOMINATOR).unwrap();
+        bob.add_item(&mut vault, 3 * BPS_DENOMINATOR).unwrap();
+
+        assert_eq!(vault.accrue_rewards(START + INTERVAL - 1).unwrap(), 0);
+        assert_eq!(vault.accrue_rewards(START + INTERVAL).unwrap(), 1);
+
+        assert_eq!(alice.claim(&mut vault).unwrap(), 250);
+        assert_eq!(bob.claim(&mut vault).unwrap(), 750);
+        assert_eq!(alice.claim(&mut vault).unwrap(), 0);
+    }
+
+    #[test]
+    fn late_staker_only_earns_later_rounds() {
+        let mut vault = test_vault(EMISSION_MODE_ROUNDS, 100_000);
+        let mut alice = test_user();
+        let mut bob = test_user();
+        alice.add_item(&mut vault, BPS_DENOMINATOR).unwrap();
+        vault.accrue_rewards(START + INTERVAL).unwrap();
+
+        bob.add_item(&mut vault, BPS_DENOMINATOR).unwrap();
+        assert_eq!(vault.accrue_rewards(START + 3 * INTERVAL).unwrap(), 2);
+
+        assert_eq!(alice.claim(&mut vault).unwrap(), 2 * PAYOUT);
+        assert_eq!(bob.claim(&mut vault).unwrap(), PAYOUT);
+        assert_eq!(vault.total_rewards_accrued, 3 * PAYOUT);
+    }
+
+    #[test]
+    fn booster_scales_share() {
+        let mut vault = test_vault(EMISSION_MODE_ROUNDS, 100_000);
+        let mut alice = test_user();
+        let mut bob = test_user();
+        alice.add_item(&mut vault, BPS_DENOMINATOR).unwrap();
+        alice.add_booster(&mut vault, Pubkey::new_unique(), 2 * BPS_DENOMINATOR).unwrap();
+        bob.add_item(&mut vault, BPS_DENOMINATOR).unwrap();
+        assert_eq!(vault.total_staked_weight, 3 * BPS_DENOMINATOR);
+
+        vault.accrue_rewards(START + 3 * INTERVAL).unwrap();
+        assert_eq!(alice.claim(&mut vault).unwrap(), 2 * PAYOUT);
+        assert_eq!(bob.claim(&mut vault).unwrap(), PAYOUT);
+    }
+
+    #[test]
+    fn empty_vault_accrues_nothing() {
+        let mut vault = test_vault(EMISSION_MODE_ROUNDS, 100_000);
+        vault.accrue_rewards(START + 5 * INTERVAL).unwrap();
+        assert_eq!(vault.total_rewards_accrued, 0);
+        assert_eq!(vault.reward_per_share, 0);
+    }
+
+    #[test]
+    fn accrual_capped_by_budget() {
+        let mut vault = test_vault(EMISSION_MODE_ROUNDS, 1_500);
+        let mut alice = test_user();
+        alice.add_item(&mut vault, BPS_DENOMINATOR).unwrap();
+
+        vault.accrue_rewards(START + 3 * INTERVAL).unwrap();
+        assert_eq!(vault.total_rewards_accrued, 1_500);
+        assert_eq!(vault.remaining_rewards().unwrap(), 0);
+        assert_eq!(alice.claim(&mut vault).unwrap(), 1_500);
+    }
+
+    #[test]
+    fn continuous_accrues_per_second() {
+        let mut vault = test_vault(EMISSION_MODE_CONTINUOUS, 100_000);
+        let mut alice = test_user();
+        alice.add_item(&mut vault, BPS_DENOMINATOR).unwrap();
+
+        vault.accrue_rewards(START + INTERVAL / 2).unwrap();
+        assert_eq!(alice.claim(&mut vault).unwrap(), PAYOUT / 2);
+        vault.accrue_rewards(START + INTERVAL / 2).unwrap();
+        assert_eq!(alice.claim(&mut vault).unwrap(), 0);
+    }
+
+    #[test]
+    fn payout_params_rebase_on_current_round() {
+        let mut vault = test_vault(EMISSION_MODE_ROUNDS, 100_000);
+        let mut alice = test_user();
+        alice.add_item(&mut vault, BPS_DENOMINATOR).unwrap();
+
+        vault.set_payout_params(2 * INTERVAL, PAYOUT, START + 2 * INTERVAL + 50).unwrap();
+        assert_eq!(vault.total_rewards_accrued, 2 * PAYOUT);
+        assert_eq!(vault.payout_schedule_started_time, START + 2 * INTERVAL);
+        assert_eq!(vault.payout_round, 1);
+
+        assert_eq!(vault.accrue_rewards(START + 3 * INTERVAL).unwrap(), 0);
+        assert_eq!(vault.accrue_rewards(START + 4 * INTERVAL).unwrap(), 1);
+        assert_eq!(alice.claim(&mut vault).unwrap(), 3 * PAYOUT);
+    }
+
+    #[test]
+    fn extending_ended_emissions_skips_the_gap() {
+        let mut vault = test_vault(EMISSION_MODE_CONTINUOUS, 100_000);
+        vault.emission_end_time = START + INTERVAL;
+        let mut alice = test_user();
+        alice.add_item(&mut vault, BPS_DENOMINATOR).unwrap();
+
+        vault.set_emission_end_time(0, START + 3 * INTERVAL).unwrap();
+        assert_eq!(vault.total_rewards_accrued, PAYOUT);
+        vault.accrue_rewards(START + 4 * INTERVAL).unwrap();
+        assert_eq!(alice.claim(&mut vault).unwrap(), 2 * PAYOUT);
+    }
+
+    #[test]
+    fn forfeit_returns_rewards_to_budget() {
+        let mut vault = test_vault(EMISSION_MODE_ROUNDS, 100_000);
+        let mut alice = test_user();
+        alice.add_item(&mut vault, BPS_DENOMINATOR).unwrap();
+        vault.accrue_rewards(START + INTERVAL).unwrap();
+
+        assert_eq!(alice.forfeit_item(&mut vault, BPS_DENOMINATOR), PAYOUT);
+        assert_eq!(vault.total_rewards_accrued, 0);
+        assert_eq!(vault.total_staked_weight, 0);
+        assert_eq!(alice.claim(&mut vault).unwrap(), 0);
+    }
+
+    #[test]
+    fn record_claim_splits_fee() {
+        let mut vault = test_vault(EMISSION_MODE_ROUNDS, 100_000);
+        vault.claim_fee_bps = 1_000;
+
+        assert_eq!(vault.record_claim(1_000).unwrap(), (100, 900));
+        assert_eq!(vault.total_claim_fees, 100);
+        assert_eq!(vault.total_fees_collected, 100);
+        assert_eq!(vault.total_amount, 99_000);
+    }
+
+    #[test]
+    fn set_multisig_validates_signers() {
+        let mut vault = Vault::default();
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        assert!(vault.set_multisig(&[a, a], 1).is_err());
+        assert!(vault.set_multisig(&[a], 2).is_err());
+        assert!(vault.set_multisig(&[Pubkey::default()], 1).is_err());
+        vault.set_multisig(&[a, b], 2).unwrap();
+        assert!(vault.multisig_enabled() && vault.is_multisig_signer(&b));
+        vault.set_multisig(&[], 0).unwrap();
+        assert!(!vault.multisig_enabled());
+    }
+
+    #[test]
+    fn proposal_counts_current_signers_once() {
+        let mut vault = Vault::default();
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        vault.set_multisig(&[a, b], 2).unwrap();
+        let mut proposal = Proposal {
+            vault: Pubkey::default(),
+            index: 0,
+            proposer: a,
+            action: ProposalAction::CancelPendingChanges,
+            approvers: [Pubkey::default(); MAX_MULTISIG_SIGNERS],
+            created_at: 0,
+            executed: false,
+            bump: 0,
+        };
+
+        proposal.approve(a).unwrap();
+        assert!(proposal.approve(a).is_err());
+        proposal.approve(b).unwrap();
+        assert_eq!(proposal.approval_count(&vault), 2);
+
+        vault.set_multisig(&[a], 1).unwrap();
+        assert_eq!(proposal.approval_count(&vault), 1);
+    }
+
+    #[test]
+    fn drains_wait_for_eta() {
+        let mut vault = test_vault(EMISSION_MODE_ROUNDS, 100_000);
+        assert!(vault.take_drain(500, START).unwrap());
+
+        vault.timelock_delay = INTERVAL;
+        assert!(!vault.take_drain(500, START).unwrap());
+        assert!(vault.take_drain(500, START + INTERVAL - 1).is_err());
+        assert!(vault.take_drain(500, START + INTERVAL).unwrap());
+        assert_eq!(vault.pending_drain, PendingDrain::default());
+
+        assert!(!vault.take_drain(700, START).unwrap());
+        vault.cancel_pending_changes();
+        assert!(!vault.take_drain(700, START + INTERVAL).unwrap());
+    }
+
+    #[test]
+    fn pending_changes_wait_for_eta() {
+        let mut vault = test_vault(EMISSION_MODE_ROUNDS, 100_000);
+        vault.timelock_delay = INTERVAL;
+        vault.queue_fees(10, 20, START).unwrap();
+        vault.queue_claim_fee(500, START).unwrap();
+        assert_eq!(vault.stake_fee, 0);
+
+        assert!(!vault.execute_pending_changes(START + INTERVAL - 1).unwrap());
+        assert!(vault.execute_pending_changes(START + INTERVAL).unwrap());
+        assert_eq!((vault.stake_fee, vault.unstake_fee, vault.claim_fee_bps), (10, 20, 500));
+        assert!(!vault.execute_pending_changes(START + INTERVAL).unwrap());
+
+        vault.queue_fees(30, 40, START + INTERVAL).unwrap();
+        vault.cancel_pending_changes();
+        assert!(!vault.execute_pending_changes(START + 3 * INTERVAL).unwrap());
+        assert_eq!(vault.stake_fee, 10);
+    }
 }
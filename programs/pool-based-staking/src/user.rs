@@ -1,28 +1,116 @@
 use anchor_lang::prelude::*;
 
+use crate::constants::*;
+use crate::errors::*;
+use crate::events::*;
 use crate::ins::*;
+use crate::state::current_timestamp;
 
 
 pub fn handle_create_stake_account(ctx: Context<CreateStakeAccount>) -> Result<()> {
   let user = &mut ctx.accounts.user;
   user.key = ctx.accounts.creator.key();
+  user.vault = ctx.accounts.vault.key();
   user.mint_staked_count = 0;
-  user.eligible_count = 0;
-  user.staked_items = vec![];
+  user.staked_weight = 0;
+  user.booster_mint = Pubkey::default();
+  user.booster_multiplier_bps = BPS_DENOMINATOR;
+  user.reward_debt = 0;
+  user.pending_rewards = 0;
   user.bump = *ctx.bumps.get("user").unwrap();
+
+  emit!(StakeAccountCreated {
+    vault: user.vault,
+    user: user.key,
+    timestamp: current_timestamp()? as i64,
+  });
+
   Ok(())
 }
 
 pub fn handle_claim_rewards(ctx: Context<Claim>) -> Result<()> {
   let vault =  &mut ctx.accounts.vault.load_mut()?;
   let user = &mut ctx.accounts.user;
+  require!(!vault.pays_token_rewards(), CustomError::InvalidRewardMode);
+  require!(!vault.is_paused(PAUSE_CLAIM) && !vault.in_emergency(), CustomError::ClaimingPaused);
+
+  vault.update_payout_round()?;
+  let staker_earned_amount = user.claim(vault)?;
+  let (fee, staker_amount) = vault.record_claim(staker_earned_amount)?;
 
-  vault.update_payout_round();
-  let staker_earned_amount = user.claim(vault);
+  let vault_info = ctx.accounts.vault.to_account_info();
+  let rent_exempt_minimum = Rent::get()?.minimum_balance(vault_info.data_len());
+  let remaining_lamports = vault_info.lamports().checked_sub(staker_earned_amount).ok_or(CustomError::InsufficientVaultBalance)?;
+  require!(
+    remaining_lamports >= vault.required_lamports(rent_exempt_minimum)?,
+    CustomError::InsufficientVaultBalance
+  );
+  **vault_info.try_borrow_mut_lamports()? -= staker_earned_amount;
+  **ctx.accounts.staker.try_borrow_mut_lamports()? += staker_amount;
+  **ctx.accounts.fee_treasury.try_borrow_mut_lamports()? += fee;
 
-  **ctx.accounts.vault.to_account_info().try_borrow_mut_lamports()? -= staker_earned_amount;
-  **ctx.accounts.staker.try_borrow_mut_lamports()? += staker_earned_amount;  
+  emit!(Claimed {
+    vault: ctx.accounts.vault.key(),
+    user: ctx.accounts.staker.key(),
+    mint: Pubkey::default(),
+    amount: staker_amount,
+    fee,
+    timestamp: current_timestamp()? as i64,
+  });
 
   Ok(())
 }
 
+pub fn handle_claim_token_rewards(ctx: Context<ClaimToken>) -> Result<()> {
+  let vault =  &mut ctx.accounts.vault.load_mut()?;
+  let user = &mut ctx.accounts.user;
+  require!(vault.pays_token_rewards(), CustomError::InvalidRewardMode);
+  require!(!vault.is_paused(PAUSE_CLAIM) && !vault.in_emergency(), CustomError::ClaimingPaused);
+
+  vault.update_payout_round()?;
+  let staker_earned_amount = user.claim(vault)?;
+  let (fee, staker_amount) = vault.record_claim(staker_earned_amount)?;
+
+  let token_vault_bump = vault.bump;
+  let pool_id = vault.pool_id;
+  let seeds = &[
+    b"vault".as_ref(),
+    pool_id.as_ref(),
+    &[token_vault_bump],
+  ];
+  let signer = &[&seeds[..]];
+  let cpi_context = CpiContext::new_with_signer(
+    ctx.accounts.token_program.to_account_info(),
+    anchor_spl::token::Transfer {
+      from: ctx.accounts.vault_reward_account.to_account_info(),
+      to: ctx.accounts.staker_reward_account.to_account_info(),
+      authority: ctx.accounts.vault.to_account_info(),
+    },
+    signer
+  );
+  anchor_spl::token::transfer(cpi_context, staker_amount)?;
+
+  if fee > 0 {
+    let cpi_context = CpiContext::new_with_signer(
+      ctx.accounts.token_program.to_account_info(),
+      anchor_spl::token::Transfer {
+        from: ctx.accounts.vault_reward_account.to_account_info(),
+        to: ctx.accounts.fee_treasury_reward_account.to_account_info(),
+        authority: ctx.accounts.vault.to_account_info(),
+      },
+      signer
+    );
+    anchor_spl::token::transfer(cpi_context, fee)?;
+  }
+
+  emit!(Claimed {
+    vault: ctx.accounts.vault.key(),
+    user: ctx.accounts.staker.key(),
+    mint: vault.reward_mint,
+    amount: staker_amount,
+    fee,
+    timestamp: current_timestamp()? as i64,
+  });
+
+  Ok(())
+}
@@ -1,37 +1,195 @@
+use crate::constants::*;
 use crate::errors::*;
+use crate::events::*;
 use crate::ins::*;
+use crate::state::*;
 
 use anchor_lang::prelude::*;
 
+#[allow(clippy::too_many_arguments)]
 pub fn handle_vault_initialization(
   ctx: Context<InitializeVault>,
+  pool_id: Pubkey,
   creator_address: Pubkey,
+  collection_mint: Pubkey,
   payout_interval: u64,
   payout_amount: u64,
   stake_fee: u64,
   unstake_fee: u64,
 ) -> Result<()> {
+  require!(payout_interval > 0, CustomError::InvalidInterval);
+  require!(stake_fee <= MAX_FEE && unstake_fee <= MAX_FEE, CustomError::InvalidFee);
   let mut vault = ctx.accounts.vault.load_init()?;
 
   vault.authority = ctx.accounts.authority.key();
+  vault.operator = ctx.accounts.authority.key();
+  vault.treasurer = ctx.accounts.authority.key();
+  vault.pool_id = pool_id;
   vault.creator_address = creator_address;
+  vault.collection_mint = collection_mint;
   vault.payout_interval = payout_interval;
   vault.payout_amount = payout_amount;
   vault.stake_fee = stake_fee;
   vault.unstake_fee = unstake_fee;
   vault.bump = *ctx.bumps.get("vault").unwrap();
+  vault.fee_treasury_bump = *ctx.bumps.get("fee_treasury").unwrap();
+
+  // Make the fee treasury rent exempt so fees of any size can be paid into it.
+  let fee_treasury_rent = Rent::get()?.minimum_balance(0);
+  let fee_treasury_lamports = ctx.accounts.fee_treasury.lamports();
+  if fee_treasury_lamports < fee_treasury_rent {
+    let cpi_context = CpiContext::new(
+      ctx.accounts.system_program.to_account_info(),
+      anchor_lang::system_program::Transfer {
+        from: ctx.accounts.authority.to_account_info(),
+        to: ctx.accounts.fee_treasury.to_account_info(),
+      }
+    );
+    anchor_lang::system_program::transfer(cpi_context, fee_treasury_rent - fee_treasury_lamports)?;
+  }
+
+  emit!(VaultInitialized {
+    vault: ctx.accounts.vault.key(),
+    authority: vault.authority,
+    pool_id,
+    creator_address,
+    collection_mint,
+    payout_interval,
+    payout_amount,
+    stake_fee,
+    unstake_fee,
+    timestamp: current_timestamp()? as i64,
+  });
+
+  Ok(())
+}
+
+pub fn handle_set_fees(ctx: Context<UpdateVault>, stake_fee: u64, unstake_fee: u64) -> Result<()> {
+  require_keys_eq!(
+    ctx.accounts.vault.load()?.authority,
+    ctx.accounts.authority.key(),
+    CustomError::Unauthorized
+  );
+  require!(stake_fee <= MAX_FEE && unstake_fee <= MAX_FEE, CustomError::InvalidFee);
+  let vault = &mut ctx.accounts.vault.load_mut()?;
+  require!(!vault.multisig_enabled(), CustomError::MultisigRequired);
+
+  let now = current_timestamp()?;
+  vault.queue_fees(stake_fee, unstake_fee, now)?;
+
+  emit!(VaultUpdated {
+    vault: ctx.accounts.vault.key(),
+    authority: ctx.accounts.authority.key(),
+    change: VaultChange::Fees { stake_fee, unstake_fee },
+    timestamp: now as i64,
+  });
 
   Ok(())
 }
 
-pub fn handle_vault_update(
+pub fn handle_set_payout_params(
   ctx: Context<UpdateVault>,
-  new_authority: Pubkey,
-  creator_address: Pubkey,
   payout_interval: u64,
   payout_amount: u64,
-  stake_fee: u64,
-  unstake_fee: u64,
+) -> Result<()> {
+  require_keys_eq!(
+    ctx.accounts.vault.load()?.authority,
+    ctx.accounts.authority.key(),
+    CustomError::Unauthorized
+  );
+  require!(payout_interval > 0, CustomError::InvalidInterval);
+  let vault = &mut ctx.accounts.vault.load_mut()?;
+  require!(!vault.multisig_enabled(), CustomError::MultisigRequired);
+
+  let now = current_timestamp()?;
+  vault.queue_payout_params(payout_interval, payout_amount, now)?;
+
+  emit!(VaultUpdated {
+    vault: ctx.accounts.vault.key(),
+    authority: ctx.accounts.authority.key(),
+    change: VaultChange::PayoutParams { payout_interval, payout_amount },
+    timestamp: now as i64,
+  });
+
+  Ok(())
+}
+
+pub fn handle_set_timelock_delay(ctx: Context<UpdateVault>, timelock_delay: u64) -> Result<()> {
+  require_keys_eq!(
+    ctx.accounts.vault.load()?.authority,
+    ctx.accounts.authority.key(),
+    CustomError::Unauthorized
+  );
+  require!(timelock_delay <= MAX_TIMELOCK_DELAY, CustomError::InvalidTimelockDelay);
+  let vault = &mut ctx.accounts.vault.load_mut()?;
+  require!(!vault.multisig_enabled(), CustomError::MultisigRequired);
+
+  let now = current_timestamp()?;
+  vault.queue_timelock_delay(timelock_delay, now)?;
+
+  emit!(VaultUpdated {
+    vault: ctx.accounts.vault.key(),
+    authority: ctx.accounts.authority.key(),
+    change: VaultChange::TimelockDelay { timelock_delay },
+    timestamp: now as i64,
+  });
+
+  Ok(())
+}
+
+// Anyone can apply queued changes once their timelock has passed.
+pub fn handle_execute_pending_changes(ctx: Context<ExecutePendingChanges>) -> Result<()> {
+  let vault = &mut ctx.accounts.vault.load_mut()?;
+
+  let now = current_timestamp()?;
+  require!(vault.execute_pending_changes(now)?, CustomError::NoPendingChange);
+
+  emit!(PendingChangesExecuted {
+    vault: ctx.accounts.vault.key(),
+    executor: ctx.accounts.executor.key(),
+    stake_fee: vault.stake_fee,
+    unstake_fee: vault.unstake_fee,
+    payout_interval: vault.payout_interval,
+    payout_amount: vault.payout_amount,
+    timelock_delay: vault.timelock_delay,
+    fee_mint: vault.fee_mint,
+    pass_mint: vault.pass_mint,
+    pass_discount_bps: vault.pass_discount_bps,
+    claim_fee_bps: vault.claim_fee_bps,
+    batch_fee_once: vault.batch_fee_once == 1,
+    emission_end_time: vault.emission_end_time,
+    timestamp: now as i64,
+  });
+
+  Ok(())
+}
+
+pub fn handle_cancel_pending_changes(ctx: Context<UpdateVault>) -> Result<()> {
+  require_keys_eq!(
+    ctx.accounts.vault.load()?.authority,
+    ctx.accounts.authority.key(),
+    CustomError::Unauthorized
+  );
+  let vault = &mut ctx.accounts.vault.load_mut()?;
+  require!(!vault.multisig_enabled(), CustomError::MultisigRequired);
+
+  vault.cancel_pending_changes();
+
+  emit!(VaultUpdated {
+    vault: ctx.accounts.vault.key(),
+    authority: ctx.accounts.authority.key(),
+    change: VaultChange::PendingChangesCancelled,
+    timestamp: current_timestamp()? as i64,
+  });
+
+  Ok(())
+}
+
+// Only gates NFTs staked from now on, staked NFTs are unaffected.
+pub fn handle_set_creator(
+  ctx: Context<UpdateVault>,
+  creator_address: Pubkey,
+  collection_mint: Pubkey,
 ) -> Result<()> {
   require_keys_eq!(
     ctx.accounts.vault.load()?.authority,
@@ -40,40 +198,464 @@ pub fn handle_vault_update(
   );
   let vault = &mut ctx.accounts.vault.load_mut()?;
 
-  vault.authority = new_authority;
   vault.creator_address = creator_address;
-  vault.payout_interval = payout_interval;
-  vault.payout_amount = payout_amount;
-  vault.stake_fee = stake_fee;
-  vault.unstake_fee = unstake_fee;
+  vault.collection_mint = collection_mint;
+
+  emit!(VaultUpdated {
+    vault: ctx.accounts.vault.key(),
+    authority: ctx.accounts.authority.key(),
+    change: VaultChange::Creator { creator_address, collection_mint },
+    timestamp: current_timestamp()? as i64,
+  });
+
+  Ok(())
+}
+
+// Proposing the default key cancels a pending transfer.
+pub fn handle_propose_authority(ctx: Context<UpdateVault>, new_authority: Pubkey) -> Result<()> {
+  require_keys_eq!(
+    ctx.accounts.vault.load()?.authority,
+    ctx.accounts.authority.key(),
+    CustomError::Unauthorized
+  );
+  let vault = &mut ctx.accounts.vault.load_mut()?;
+  require!(!vault.multisig_enabled(), CustomError::MultisigRequired);
+
+  vault.pending_authority = new_authority;
+
+  emit!(AuthorityProposed {
+    vault: ctx.accounts.vault.key(),
+    authority: vault.authority,
+    pending_authority: new_authority,
+    timestamp: current_timestamp()? as i64,
+  });
+
+  Ok(())
+}
+
+pub fn handle_accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+  let vault = &mut ctx.accounts.vault.load_mut()?;
+  require_keys_neq!(vault.pending_authority, Pubkey::default(), CustomError::NoPendingAuthority);
+  require_keys_eq!(
+    vault.pending_authority,
+    ctx.accounts.pending_authority.key(),
+    CustomError::Unauthorized
+  );
+
+  let previous_authority = vault.authority;
+  vault.authority = vault.pending_authority;
+  vault.pending_authority = Pubkey::default();
+
+  emit!(AuthorityAccepted {
+    vault: ctx.accounts.vault.key(),
+    previous_authority,
+    authority: vault.authority,
+    timestamp: current_timestamp()? as i64,
+  });
+
+  Ok(())
+}
+
+pub fn handle_set_roles(ctx: Context<UpdateVault>, operator: Pubkey, treasurer: Pubkey) -> Result<()> {
+  require_keys_eq!(
+    ctx.accounts.vault.load()?.authority,
+    ctx.accounts.authority.key(),
+    CustomError::Unauthorized
+  );
+  let vault = &mut ctx.accounts.vault.load_mut()?;
+  require!(!vault.multisig_enabled(), CustomError::MultisigRequired);
+
+  vault.operator = operator;
+  vault.treasurer = treasurer;
+
+  emit!(RolesUpdated {
+    vault: ctx.accounts.vault.key(),
+    authority: vault.authority,
+    operator,
+    treasurer,
+    timestamp: current_timestamp()? as i64,
+  });
+
+  Ok(())
+}
+
+// `flags` is any combination of PAUSE_STAKE, PAUSE_CLAIM and PAUSE_FUND.
+pub fn handle_pause(ctx: Context<UpdateVault>, flags: u8) -> Result<()> {
+  require_keys_eq!(
+    ctx.accounts.vault.load()?.operator,
+    ctx.accounts.authority.key(),
+    CustomError::Unauthorized
+  );
+  let vault = &mut ctx.accounts.vault.load_mut()?;
+
+  vault.paused |= flags;
+
+  emit!(PauseUpdated {
+    vault: ctx.accounts.vault.key(),
+    operator: ctx.accounts.authority.key(),
+    paused: vault.paused,
+    emergency_mode: vault.in_emergency(),
+    timestamp: current_timestamp()? as i64,
+  });
+
+  Ok(())
+}
+
+pub fn handle_unpause(ctx: Context<UpdateVault>, flags: u8) -> Result<()> {
+  require_keys_eq!(
+    ctx.accounts.vault.load()?.operator,
+    ctx.accounts.authority.key(),
+    CustomError::Unauthorized
+  );
+  let vault = &mut ctx.accounts.vault.load_mut()?;
+
+  vault.paused &= !flags;
+
+  emit!(PauseUpdated {
+    vault: ctx.accounts.vault.key(),
+    operator: ctx.accounts.authority.key(),
+    paused: vault.paused,
+    emergency_mode: vault.in_emergency(),
+    timestamp: current_timestamp()? as i64,
+  });
+
+  Ok(())
+}
+
+pub fn handle_set_emergency_mode(ctx: Context<UpdateVault>, enabled: bool) -> Result<()> {
+  require_keys_eq!(
+    ctx.accounts.vault.load()?.operator,
+    ctx.accounts.authority.key(),
+    CustomError::Unauthorized
+  );
+  let vault = &mut ctx.accounts.vault.load_mut()?;
+
+  vault.emergency_mode = enabled as u8;
+
+  emit!(PauseUpdated {
+    vault: ctx.accounts.vault.key(),
+    operator: ctx.accounts.authority.key(),
+    paused: vault.paused,
+    emergency_mode: enabled,
+    timestamp: current_timestamp()? as i64,
+  });
 
   Ok(())
 }
 
 pub fn handle_fund(ctx: Context<FundSolVault>, amount: u64) -> Result<()> {
+  require_keys_eq!(
+    ctx.accounts.vault.load()?.treasurer.key(),
+    ctx.accounts.funder.key(),
+    CustomError::Unauthorized
+  );
+  require!(!ctx.accounts.vault.load()?.pays_token_rewards(), CustomError::InvalidRewardMode);
+  require!(!ctx.accounts.vault.load()?.is_paused(PAUSE_FUND), CustomError::FundingPaused);
+
+  let cpi_context = CpiContext::new(
+    ctx.accounts.system_program.to_account_info(),
+    anchor_lang::system_program::Transfer {
+      from: ctx.accounts.funder.to_account_info(),
+      to: ctx.accounts.vault.to_account_info(),
+    }
+  );
+  anchor_lang::system_program::transfer(cpi_context, amount)?;
+
   let vault = &mut ctx.accounts.vault.load_mut()?;
-  vault.total_amount = vault.total_amount.checked_add(amount).unwrap();
-  **ctx.accounts.vault.to_account_info().try_borrow_mut_lamports()? += amount;
-  **ctx.accounts.funder.try_borrow_mut_lamports()? -= amount;
+  vault.record_fund(amount)?;
+
+  emit!(Funded {
+    vault: ctx.accounts.vault.key(),
+    funder: ctx.accounts.funder.key(),
+    mint: Pubkey::default(),
+    amount,
+    total_amount: vault.total_amount,
+    timestamp: current_timestamp()? as i64,
+  });
 
   Ok(())
 }
 
 pub fn handle_drain(ctx: Context<DrainSolVault>, amount: u64) -> Result<()> {
   require_keys_eq!(
-    ctx.accounts.vault.load()?.authority.key(),
+    ctx.accounts.vault.load()?.treasurer.key(),
     ctx.accounts.funder.key(),
     CustomError::Unauthorized
   );
   let vault = &mut ctx.accounts.vault.load_mut()?;
-  vault.total_amount = vault.total_amount.checked_sub(amount).unwrap();
-  **ctx.accounts.vault.to_account_info().try_borrow_mut_lamports()? -= amount;
+  require!(!vault.multisig_enabled(), CustomError::MultisigRequired);
+  require!(!vault.pays_token_rewards(), CustomError::InvalidRewardMode);
+
+  let now = current_timestamp()?;
+  if !vault.take_drain(amount, now)? {
+    emit!(DrainQueued {
+      vault: ctx.accounts.vault.key(),
+      authority: ctx.accounts.funder.key(),
+      amount,
+      eta: vault.pending_drain.eta,
+      timestamp: now as i64,
+    });
+    return Ok(());
+  }
+  vault.record_drain(amount)?;
+
+  let vault_info = ctx.accounts.vault.to_account_info();
+  let rent_exempt_minimum = Rent::get()?.minimum_balance(vault_info.data_len());
+  let remaining_lamports = vault_info.lamports().checked_sub(amount).ok_or(CustomError::InsufficientVaultBalance)?;
+  require!(
+    remaining_lamports >= vault.required_lamports(rent_exempt_minimum)?,
+    CustomError::InsufficientVaultBalance
+  );
+  **vault_info.try_borrow_mut_lamports()? -= amount;
   **ctx.accounts.funder.try_borrow_mut_lamports()? += amount;
 
+  emit!(Drained {
+    vault: ctx.accounts.vault.key(),
+    authority: ctx.accounts.funder.key(),
+    mint: Pubkey::default(),
+    amount,
+    total_amount: vault.total_amount,
+    timestamp: current_timestamp()? as i64,
+  });
+
   Ok(())
 }
 
-pub fn handle_start_payout_schedule(ctx: Context<UpdateVault>) -> Result<()> {
+pub fn handle_withdraw_fees(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
+  require_keys_eq!(
+    ctx.accounts.vault.load()?.treasurer.key(),
+    ctx.accounts.authority.key(),
+    CustomError::Unauthorized
+  );
+
+  // The treasury keeps its rent exemption.
+  let fee_treasury_rent = Rent::get()?.minimum_balance(0);
+  let remaining_lamports = ctx.accounts.fee_treasury.lamports()
+    .checked_sub(amount)
+    .ok_or(CustomError::InsufficientVaultBalance)?;
+  require!(remaining_lamports >= fee_treasury_rent, CustomError::InsufficientVaultBalance);
+
+  let vault_key = ctx.accounts.vault.key();
+  let fee_treasury_bump = ctx.accounts.vault.load()?.fee_treasury_bump;
+  let seeds = &[
+    b"fee_treasury".as_ref(),
+    vault_key.as_ref(),
+    &[fee_treasury_bump],
+  ];
+  let signer = &[&seeds[..]];
+  let cpi_context = CpiContext::new_with_signer(
+    ctx.accounts.system_program.to_account_info(),
+    anchor_lang::system_program::Transfer {
+      from: ctx.accounts.fee_treasury.to_account_info(),
+      to: ctx.accounts.recipient.to_account_info(),
+    },
+    signer
+  );
+  anchor_lang::system_program::transfer(cpi_context, amount)?;
+
+  let vault = &mut ctx.accounts.vault.load_mut()?;
+  if vault.fee_mint == Pubkey::default() {
+    vault.total_fees_withdrawn = vault.total_fees_withdrawn.checked_add(amount).ok_or(CustomError::MathOverflow)?;
+  }
+
+  emit!(FeesWithdrawn {
+    vault: vault_key,
+    recipient: ctx.accounts.recipient.key(),
+    mint: Pubkey::default(),
+    amount,
+    timestamp: current_timestamp()? as i64,
+  });
+
+  Ok(())
+}
+
+pub fn handle_initialize_fee_token_account(ctx: Context<InitializeFeeTokenAccount>) -> Result<()> {
+  require_keys_eq!(
+    ctx.accounts.vault.load()?.authority.key(),
+    ctx.accounts.authority.key(),
+    CustomError::Unauthorized
+  );
+
+  Ok(())
+}
+
+pub fn handle_withdraw_fee_tokens(ctx: Context<WithdrawFeeTokens>, amount: u64) -> Result<()> {
+  require_keys_eq!(
+    ctx.accounts.vault.load()?.treasurer.key(),
+    ctx.accounts.authority.key(),
+    CustomError::Unauthorized
+  );
+
+  let vault_key = ctx.accounts.vault.key();
+  let fee_treasury_bump = ctx.accounts.vault.load()?.fee_treasury_bump;
+  let seeds = &[
+    b"fee_treasury".as_ref(),
+    vault_key.as_ref(),
+    &[fee_treasury_bump],
+  ];
+  let signer = &[&seeds[..]];
+  let cpi_context = CpiContext::new_with_signer(
+    ctx.accounts.token_program.to_account_info(),
+    anchor_spl::token::Transfer {
+      from: ctx.accounts.fee_treasury_token_account.to_account_info(),
+      to: ctx.accounts.recipient_token_account.to_account_info(),
+      authority: ctx.accounts.fee_treasury.to_account_info(),
+    },
+    signer
+  );
+  anchor_spl::token::transfer(cpi_context, amount)?;
+
+  let mint = ctx.accounts.fee_treasury_token_account.mint;
+  let vault = &mut ctx.accounts.vault.load_mut()?;
+  if vault.fee_mint == mint {
+    vault.total_fees_withdrawn = vault.total_fees_withdrawn.checked_add(amount).ok_or(CustomError::MathOverflow)?;
+  }
+
+  emit!(FeesWithdrawn {
+    vault: vault_key,
+    recipient: ctx.accounts.recipient_token_account.key(),
+    mint,
+    amount,
+    timestamp: current_timestamp()? as i64,
+  });
+
+  Ok(())
+}
+
+pub fn handle_initialize_reward_token_account(ctx: Context<InitializeRewardTokenAccount>) -> Result<()> {
+  require_keys_eq!(
+    ctx.accounts.vault.load()?.authority.key(),
+    ctx.accounts.authority.key(),
+    CustomError::Unauthorized
+  );
+  let vault = &mut ctx.accounts.vault.load_mut()?;
+
+  // The reward currency can only be switched before any SOL rewards were funded.
+  require!(
+    !vault.pays_token_rewards() && vault.total_amount == 0,
+    CustomError::InvalidRewardMode
+  );
+  vault.reward_mint = ctx.accounts.reward_mint.key();
+
+  emit!(VaultUpdated {
+    vault: ctx.accounts.vault.key(),
+    authority: ctx.accounts.authority.key(),
+    change: VaultChange::RewardMint { reward_mint: vault.reward_mint },
+    timestamp: current_timestamp()? as i64,
+  });
+
+  Ok(())
+}
+
+pub fn handle_fund_token(ctx: Context<FundTokenVault>, amount: u64) -> Result<()> {
+  require_keys_eq!(
+    ctx.accounts.vault.load()?.treasurer.key(),
+    ctx.accounts.funder.key(),
+    CustomError::Unauthorized
+  );
+  let vault = &mut ctx.accounts.vault.load_mut()?;
+  require!(vault.pays_token_rewards(), CustomError::InvalidRewardMode);
+  require!(!vault.is_paused(PAUSE_FUND), CustomError::FundingPaused);
+  vault.record_fund(amount)?;
+
+  let cpi_context = CpiContext::new(
+    ctx.accounts.token_program.to_account_info(),
+    anchor_spl::token::Transfer {
+      from: ctx.accounts.funder_token_account.to_account_info(),
+      to: ctx.accounts.vault_reward_account.to_account_info(),
+      authority: ctx.accounts.funder.to_account_info(),
+    }
+  );
+  anchor_spl::token::transfer(cpi_context, amount)?;
+
+  emit!(Funded {
+    vault: ctx.accounts.vault.key(),
+    funder: ctx.accounts.funder.key(),
+    mint: vault.reward_mint,
+    amount,
+    total_amount: vault.total_amount,
+    timestamp: current_timestamp()? as i64,
+  });
+
+  Ok(())
+}
+
+pub fn handle_drain_token(ctx: Context<DrainTokenVault>, amount: u64) -> Result<()> {
+  require_keys_eq!(
+    ctx.accounts.vault.load()?.treasurer.key(),
+    ctx.accounts.funder.key(),
+    CustomError::Unauthorized
+  );
+  let vault = &mut ctx.accounts.vault.load_mut()?;
+  require!(!vault.multisig_enabled(), CustomError::MultisigRequired);
+  require!(vault.pays_token_rewards(), CustomError::InvalidRewardMode);
+
+  let now = current_timestamp()?;
+  if !vault.take_drain(amount, now)? {
+    emit!(DrainQueued {
+      vault: ctx.accounts.vault.key(),
+      authority: ctx.accounts.funder.key(),
+      amount,
+      eta: vault.pending_drain.eta,
+      timestamp: now as i64,
+    });
+    return Ok(());
+  }
+  vault.record_drain(amount)?;
+
+  let token_vault_bump = vault.bump;
+  let pool_id = vault.pool_id;
+  let seeds = &[
+    b"vault".as_ref(),
+    pool_id.as_ref(),
+    &[token_vault_bump],
+  ];
+  let signer = &[&seeds[..]];
+  let cpi_context = CpiContext::new_with_signer(
+    ctx.accounts.token_program.to_account_info(),
+    anchor_spl::token::Transfer {
+      from: ctx.accounts.vault_reward_account.to_account_info(),
+      to: ctx.accounts.funder_token_account.to_account_info(),
+      authority: ctx.accounts.vault.to_account_info(),
+    },
+    signer
+  );
+  anchor_spl::token::transfer(cpi_context, amount)?;
+
+  emit!(Drained {
+    vault: ctx.accounts.vault.key(),
+    authority: ctx.accounts.funder.key(),
+    mint: vault.reward_mint,
+    amount,
+    total_amount: vault.total_amount,
+    timestamp: current_timestamp()? as i64,
+  });
+
+  Ok(())
+}
+
+pub fn handle_update_weights_root(ctx: Context<UpdateVault>, weights_root: [u8; 32]) -> Result<()> {
+  require_keys_eq!(
+    ctx.accounts.vault.load()?.authority.key(),
+    ctx.accounts.authority.key(),
+    CustomError::Unauthorized
+  );
+  let vault = &mut ctx.accounts.vault.load_mut()?;
+
+  // Only affects NFTs staked from now on, existing stakes keep the weight recorded on their receipt.
+  vault.weights_root = weights_root;
+
+  emit!(VaultUpdated {
+    vault: ctx.accounts.vault.key(),
+    authority: ctx.accounts.authority.key(),
+    change: VaultChange::WeightsRoot { weights_root },
+    timestamp: current_timestamp()? as i64,
+  });
+
+  Ok(())
+}
+
+pub fn handle_update_allowlist_root(ctx: Context<UpdateVault>, allowlist_root: [u8; 32]) -> Result<()> {
   require_keys_eq!(
     ctx.accounts.vault.load()?.authority.key(),
     ctx.accounts.authority.key(),
@@ -81,7 +663,242 @@ pub fn handle_start_payout_schedule(ctx: Context<UpdateVault>) -> Result<()> {
   );
   let vault = &mut ctx.accounts.vault.load_mut()?;
 
-  vault.start_payout_schedule();
+  vault.allowlist_root = allowlist_root;
+
+  emit!(VaultUpdated {
+    vault: ctx.accounts.vault.key(),
+    authority: ctx.accounts.authority.key(),
+    change: VaultChange::AllowlistRoot { allowlist_root },
+    timestamp: current_timestamp()? as i64,
+  });
+
+  Ok(())
+}
+
+pub fn handle_set_lock_tier(
+  ctx: Context<UpdateVault>,
+  index: u8,
+  duration: u64,
+  multiplier_bps: u64,
+) -> Result<()> {
+  require_keys_eq!(
+    ctx.accounts.vault.load()?.authority.key(),
+    ctx.accounts.authority.key(),
+    CustomError::Unauthorized
+  );
+  require!((index as usize) < MAX_LOCK_TIERS, CustomError::InvalidLockTier);
+  require!(multiplier_bps >= BPS_DENOMINATOR, CustomError::InvalidLockTier);
+  let vault = &mut ctx.accounts.vault.load_mut()?;
+
+  vault.lock_tiers[index as usize] = LockTier {
+    duration,
+    multiplier_bps,
+  };
+
+  emit!(VaultUpdated {
+    vault: ctx.accounts.vault.key(),
+    authority: ctx.accounts.authority.key(),
+    change: VaultChange::LockTier { index, duration, multiplier_bps },
+    timestamp: current_timestamp()? as i64,
+  });
+
+  Ok(())
+}
+
+pub fn handle_set_booster(
+  ctx: Context<UpdateVault>,
+  index: u8,
+  key: Pubkey,
+  multiplier_bps: u64,
+) -> Result<()> {
+  require_keys_eq!(
+    ctx.accounts.vault.load()?.authority.key(),
+    ctx.accounts.authority.key(),
+    CustomError::Unauthorized
+  );
+  require!((index as usize) < MAX_BOOSTERS, CustomError::InvalidBooster);
+  require!(multiplier_bps >= BPS_DENOMINATOR, CustomError::InvalidBooster);
+  let vault = &mut ctx.accounts.vault.load_mut()?;
+
+  // Users who already staked a booster keep the multiplier they staked with.
+  vault.boosters[index as usize] = Booster {
+    key,
+    multiplier_bps,
+  };
+
+  emit!(VaultUpdated {
+    vault: ctx.accounts.vault.key(),
+    authority: ctx.accounts.authority.key(),
+    change: VaultChange::Booster { index, key, multiplier_bps },
+    timestamp: current_timestamp()? as i64,
+  });
+
+  Ok(())
+}
+
+pub fn handle_set_batch_fee_mode(ctx: Context<UpdateVault>, charge_once: bool) -> Result<()> {
+  require_keys_eq!(
+    ctx.accounts.vault.load()?.authority.key(),
+    ctx.accounts.authority.key(),
+    CustomError::Unauthorized
+  );
+  let vault = &mut ctx.accounts.vault.load_mut()?;
+  require!(!vault.multisig_enabled(), CustomError::MultisigRequired);
+
+  let now = current_timestamp()?;
+  vault.queue_batch_fee_mode(charge_once as u8, now)?;
+
+  emit!(VaultUpdated {
+    vault: ctx.accounts.vault.key(),
+    authority: ctx.accounts.authority.key(),
+    change: VaultChange::BatchFeeMode { charge_once },
+    timestamp: now as i64,
+  });
+
+  Ok(())
+}
+
+// The fee treasury needs a token account for `fee_mint` before fees can be charged in it.
+pub fn handle_set_fee_config(
+  ctx: Context<UpdateVault>,
+  fee_mint: Pubkey,
+  pass_mint: Pubkey,
+  pass_discount_bps: u64,
+) -> Result<()> {
+  require_keys_eq!(
+    ctx.accounts.vault.load()?.authority.key(),
+    ctx.accounts.authority.key(),
+    CustomError::Unauthorized
+  );
+  require!(pass_discount_bps <= BPS_DENOMINATOR, CustomError::InvalidDiscount);
+  let vault = &mut ctx.accounts.vault.load_mut()?;
+  require!(!vault.multisig_enabled(), CustomError::MultisigRequired);
+
+  let now = current_timestamp()?;
+  vault.queue_fee_config(fee_mint, pass_mint, pass_discount_bps, now)?;
+
+  emit!(VaultUpdated {
+    vault: ctx.accounts.vault.key(),
+    authority: ctx.accounts.authority.key(),
+    change: VaultChange::FeeConfig { fee_mint, pass_mint, pass_discount_bps },
+    timestamp: now as i64,
+  });
+
+  Ok(())
+}
+
+pub fn handle_set_claim_fee(ctx: Context<UpdateVault>, claim_fee_bps: u64) -> Result<()> {
+  require_keys_eq!(
+    ctx.accounts.vault.load()?.authority.key(),
+    ctx.accounts.authority.key(),
+    CustomError::Unauthorized
+  );
+  require!(claim_fee_bps <= MAX_CLAIM_FEE_BPS, CustomError::InvalidFee);
+  let vault = &mut ctx.accounts.vault.load_mut()?;
+  require!(!vault.multisig_enabled(), CustomError::MultisigRequired);
+
+  let now = current_timestamp()?;
+  vault.queue_claim_fee(claim_fee_bps, now)?;
+
+  emit!(VaultUpdated {
+    vault: ctx.accounts.vault.key(),
+    authority: ctx.accounts.authority.key(),
+    change: VaultChange::ClaimFee { claim_fee_bps },
+    timestamp: now as i64,
+  });
+
+  Ok(())
+}
+
+pub fn handle_set_emission_mode(ctx: Context<UpdateVault>, emission_mode: u8) -> Result<()> {
+  require_keys_eq!(
+    ctx.accounts.vault.load()?.authority.key(),
+    ctx.accounts.authority.key(),
+    CustomError::Unauthorized
+  );
+  require!(
+    emission_mode == EMISSION_MODE_ROUNDS || emission_mode == EMISSION_MODE_CONTINUOUS,
+    CustomError::InvalidEmissionMode
+  );
+  let vault = &mut ctx.accounts.vault.load_mut()?;
+
+  // Switching modes mid-schedule would pay the current round twice or not at all.
+  require_eq!(vault.payout_schedule_started_time, 0, CustomError::ScheduleAlreadyStarted);
+  vault.emission_mode = emission_mode;
+
+  emit!(VaultUpdated {
+    vault: ctx.accounts.vault.key(),
+    authority: ctx.accounts.authority.key(),
+    change: VaultChange::EmissionMode { emission_mode },
+    timestamp: current_timestamp()? as i64,
+  });
+
+  Ok(())
+}
+
+pub fn handle_set_emission_end_time(ctx: Context<UpdateVault>, emission_end_time: u64) -> Result<()> {
+  require_keys_eq!(
+    ctx.accounts.vault.load()?.authority.key(),
+    ctx.accounts.authority.key(),
+    CustomError::Unauthorized
+  );
+  let vault = &mut ctx.accounts.vault.load_mut()?;
+  require!(!vault.multisig_enabled(), CustomError::MultisigRequired);
+
+  let now = current_timestamp()?;
+  vault.queue_emission_end_time(emission_end_time, now)?;
+
+  emit!(VaultUpdated {
+    vault: ctx.accounts.vault.key(),
+    authority: ctx.accounts.authority.key(),
+    change: VaultChange::EmissionEndTime { emission_end_time },
+    timestamp: now as i64,
+  });
+
+  Ok(())
+}
+
+pub fn handle_get_reward_runway(ctx: Context<ViewVault>) -> Result<RewardRunway> {
+  // Accrue on a copy so the runway reflects the current time without writing to the vault.
+  let mut vault = *ctx.accounts.vault.load()?;
+  let accrual_time = vault.accrual_time()?;
+  vault.accrue_rewards(accrual_time)?;
+
+  let remaining_rewards = vault.remaining_rewards()?;
+  let now = current_timestamp()?;
+  let mut runway_end_time = vault.emission_end_time;
+  if vault.payout_amount > 0 {
+    let budget_seconds: u64 = (remaining_rewards as u128)
+      .checked_mul(vault.payout_interval as u128).ok_or(CustomError::MathOverflow)?
+      .checked_div(vault.payout_amount as u128).ok_or(CustomError::MathOverflow)?
+      .try_into().unwrap_or(u64::MAX);
+    let budget_end_time = now.saturating_add(budget_seconds);
+    if runway_end_time == 0 || budget_end_time < runway_end_time {
+      runway_end_time = budget_end_time;
+    }
+  }
+
+  Ok(RewardRunway {
+    remaining_rewards,
+    runway_end_time,
+  })
+}
+
+pub fn handle_start_payout_schedule(ctx: Context<UpdateVault>) -> Result<()> {
+  require_keys_eq!(
+    ctx.accounts.vault.load()?.operator.key(),
+    ctx.accounts.authority.key(),
+    CustomError::Unauthorized
+  );
+  let vault = &mut ctx.accounts.vault.load_mut()?;
+
+  let now = current_timestamp()?;
+  vault.start_payout_schedule(now)?;
+
+  emit!(PayoutScheduleStarted {
+    vault: ctx.accounts.vault.key(),
+    timestamp: now as i64,
+  });
 
   Ok(())
 }
\ No newline at end of file